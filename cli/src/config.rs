@@ -1,6 +1,6 @@
 use crate::{
   create::CreateCommand, delete::DeleteCommand, read::ReadCommand,
-  update::UpdateCommand,
+  transfer::TransferCommand, update::UpdateCommand,
 };
 use anyhow::{anyhow, Result};
 use solana_sdk::signature::{read_keypair_file, Keypair};
@@ -18,6 +18,8 @@ pub(crate) enum Command {
   Update(UpdateCommand),
   #[structopt(about = "Deletes oracles from the blockchain")]
   Delete(DeleteCommand),
+  #[structopt(about = "Transfers ownership of oracles to other accounts")]
+  Transfer(TransferCommand),
 }
 
 #[derive(Debug)]
@@ -1,8 +1,10 @@
 use crate::config::SolanaConfig;
 use anyhow::{anyhow, Result};
 use indicatif::ProgressBar;
-use vvtec_client::{Network, Oracle};
-use solana_sdk::signer::Signer;
+use vvtec::{state::UpdatePolicy, Feed};
+use vvtec_client::{Network, Oracle, OracleId};
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use std::{convert::TryFrom, str::FromStr};
 use structopt::StructOpt;
 use tracing::{debug, error};
 
@@ -11,20 +13,56 @@ pub(crate) struct CreateCommand {
   #[structopt(
     long,
     short,
-    about = "Human readable name of the oracle. Only a-z, 0-9 and .-"
+    about = "Path of the oracle in the feeds tree, e.g. crypto.sol.usdt. \
+             Only a-z, 0-9 and -, segments separated by . Prefix it with \
+             <owner>/ to create it in the namespace of the owner. The \
+             parent feeds of the path must already exist"
   )]
   name: String,
   #[structopt(
     long,
     short,
+    allow_hyphen_values = true,
     about = "The initial feed value for the newly created oracle"
   )]
-  value: Option<u128>,
+  value: Option<i128>,
+  #[structopt(
+    long,
+    default_value = "0",
+    about = "Number of decimal places of the oracle values"
+  )]
+  decimals: u8,
+  #[structopt(
+    long,
+    default_value = "0",
+    about = "Number of past values retained on-chain by the oracle"
+  )]
+  history: u16,
+  #[structopt(
+    long,
+    default_value = "0",
+    about = "Maximum number of seconds between two updates, 0 for none"
+  )]
+  heartbeat: u32,
+  #[structopt(
+    long,
+    default_value = "0",
+    about = "Value move, in basis points, that warrants an update before \
+             the next heartbeat"
+  )]
+  deviation_bps: u16,
 }
 
 impl CreateCommand {
   pub async fn invoke(self, solana: SolanaConfig) -> Result<()> {
     let network = Network::Custom(solana.json_rpc.clone(), solana.ws_url.clone());
+    let exponent = i8::try_from(self.decimals)
+      .map(|d| -d)
+      .map_err(|_| anyhow!("too many decimals: {}", self.decimals))?;
+    let policy = UpdatePolicy {
+      heartbeat_secs: self.heartbeat,
+      deviation_bps: self.deviation_bps,
+    };
     let owner_acc = solana.keypair.pubkey();
     let funding_acc = solana.keypair.pubkey();
 
@@ -34,13 +72,45 @@ impl CreateCommand {
     let progress = ProgressBar::new_spinner();
     progress.enable_steady_tick(120);
     progress.set_message(format!("Creating oracle {}...", &self.name));
-    match Oracle::create_on_network(
-      self.name,
-      owner_acc,
-      solana.keypair,
-      self.value,
-      network,
-    ) {
+    let (namespace, path) = match self.name.split_once('/') {
+      Some((owner, path)) => (Some(Pubkey::from_str(owner)?), path),
+      None => (None, self.name.as_str()),
+    };
+    let feed = |name: &str| Feed {
+      owner: owner_acc,
+      name: name.to_owned(),
+      value: self.value,
+      exponent,
+      policy,
+      history_capacity: self.history,
+    };
+    let result = match (path.rsplit_once('.'), namespace) {
+      (Some((parent, name)), namespace) => Oracle::create_child_on_network(
+        match namespace {
+          Some(owner) => OracleId::from_owner_and_name(&owner, parent),
+          None => OracleId::from_name(parent),
+        },
+        feed(name),
+        solana.keypair,
+        network,
+      ),
+      (None, Some(namespace)) if namespace != owner_acc => {
+        progress.finish_and_clear();
+        return Err(anyhow!(
+          "only {} can create root feeds in its namespace",
+          namespace
+        ));
+      }
+      (None, Some(_)) => Oracle::create_namespaced_on_network(
+        feed(path),
+        solana.keypair,
+        network,
+      ),
+      (None, None) => {
+        Oracle::create_on_network(feed(&self.name), solana.keypair, network)
+      }
+    };
+    match result {
       Ok((oracle, tx)) => {
         progress.finish_and_clear();
         println!("New oracle created:");
@@ -4,13 +4,18 @@ use crate::config::SolanaConfig;
 use anyhow::{anyhow, Result};
 use indicatif::ProgressBar;
 use vvtec_client::{Network, Oracle, OracleId};
-use solana_sdk::signer::Signer;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
 use structopt::StructOpt;
 use tracing::{debug, error};
 
 #[derive(Debug, Clone, StructOpt)]
 pub(crate) struct DeleteCommand {
   oracle: String,
+  #[structopt(
+    long,
+    about = "The account that receives the rent, defaults to the keypair"
+  )]
+  refund_to: Option<Pubkey>,
 }
 
 impl DeleteCommand {
@@ -19,10 +24,12 @@ impl DeleteCommand {
     let network =
       Network::Custom(solana.json_rpc.clone(), solana.ws_url.clone());
     let funding_acc = solana.keypair.pubkey();
+    let refund_acc = self.refund_to.unwrap_or(funding_acc);
 
     debug!("using network: {}", &network);
     debug!("deleting oracle: {}", &oracle_id);
     debug!("funding account: {}", &funding_acc);
+    debug!("refund account: {}", &refund_acc);
 
     let oracle = Oracle::open_on_network(oracle_id, network, solana.keypair)?;
 
@@ -30,11 +37,12 @@ impl DeleteCommand {
     progress.enable_steady_tick(50);
     progress.set_message("Deleting oracle...");
 
-    match oracle.delete() {
+    match oracle.delete_to(refund_acc) {
       Ok(txhash) => {
         progress.finish_and_clear();
         println!("Oracle {} deleted: ", self.oracle);
         println!("  - address: {}", &oracle.id());
+        println!("  - refunded to: {}", &refund_acc);
         println!("  - tx: {}", &txhash);
         Ok(())
       }
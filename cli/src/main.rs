@@ -2,6 +2,7 @@ mod config;
 mod create;
 mod delete;
 mod read;
+mod transfer;
 mod update;
 
 use crate::config::Command;
@@ -17,6 +18,7 @@ async fn main() -> Result<()> {
     Command::Read(cmd) => cmd.invoke(sys.solana).await?,
     Command::Update(cmd) => cmd.invoke(sys.solana).await?,
     Command::Delete(cmd) => cmd.invoke(sys.solana).await?,
+    Command::Transfer(cmd) => cmd.invoke(sys.solana).await?,
   };
 
   Ok(())
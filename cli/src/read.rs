@@ -1,5 +1,7 @@
 use crate::config::SolanaConfig;
 use anyhow::Result;
+use chrono::Utc;
+use vvtec::state::FeedMetadata;
 use vvtec_client::{Network, Oracle, OracleId};
 use std::str::FromStr;
 use structopt::StructOpt;
@@ -8,6 +10,8 @@ use tracing::debug;
 #[derive(Debug, Clone, StructOpt)]
 pub(crate) struct ReadCommand {
   oracle: String,
+  #[structopt(long, about = "Also print the values retained on-chain")]
+  history: bool,
 }
 
 impl ReadCommand {
@@ -21,7 +25,38 @@ impl ReadCommand {
     let client = Oracle::open_on_network(oracle_id, network, solana.keypair).unwrap();
     let snapshot = client.latest_snapshot()?;
     println!("Oracle {} value is {}", client.name()?, snapshot);
+    if let Some(overdue) = snapshot.staleness(Utc::now().timestamp()) {
+      println!("Oracle is stale, last update is {}s overdue", overdue);
+    }
+    if let Some(metadata) = client.metadata()? {
+      print_metadata(&metadata);
+    }
+
+    if self.history {
+      println!("History:");
+      for entry in client.history()? {
+        println!("  - {}", entry);
+      }
+    }
 
     Ok(())
   }
 }
+
+/// Prints the metadata fields that are set.
+fn print_metadata(metadata: &FeedMetadata) {
+  let fields = [
+    ("description", metadata.description.clone()),
+    ("unit", metadata.unit.clone()),
+    ("decimals", match metadata.decimals {
+      0 => String::new(),
+      decimals => decimals.to_string(),
+    }),
+    ("sources", metadata.sources.join(", ")),
+    ("tags", metadata.tags.join(", ")),
+    ("website", metadata.website.clone()),
+  ];
+  for (name, value) in fields.iter().filter(|(_, value)| !value.is_empty()) {
+    println!("  - {}: {}", name, value);
+  }
+}
@@ -0,0 +1,72 @@
+use crate::config::SolanaConfig;
+use anyhow::{anyhow, Result};
+use indicatif::ProgressBar;
+use vvtec_client::{Network, Oracle, OracleId};
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use std::str::FromStr;
+use structopt::StructOpt;
+use tracing::{debug, error};
+
+#[derive(Debug, Clone, StructOpt)]
+pub(crate) struct TransferCommand {
+  oracle: String,
+  #[structopt(
+    required_unless = "accept",
+    about = "The account that will be able to accept the ownership"
+  )]
+  new_owner: Option<Pubkey>,
+  #[structopt(
+    long,
+    conflicts_with = "new-owner",
+    about = "Accept an ownership transfer proposed to the current keypair"
+  )]
+  accept: bool,
+}
+
+impl TransferCommand {
+  pub async fn invoke(self, solana: SolanaConfig) -> Result<()> {
+    let oracle_id = OracleId::from_str(&self.oracle)
+      .map_err(|e| anyhow!("invalid oracle {}: {}", self.oracle, e))?;
+    let network =
+      Network::Custom(solana.json_rpc.clone(), solana.ws_url.clone());
+    let signer_acc = solana.keypair.pubkey();
+
+    debug!("using network: {}", &network);
+    debug!("transferring oracle: {}", &oracle_id);
+    debug!("signer account: {}", &signer_acc);
+
+    let oracle = Oracle::open_on_network(oracle_id, network, solana.keypair)?;
+
+    let progress = ProgressBar::new_spinner();
+    progress.enable_steady_tick(50);
+
+    let result = if self.accept {
+      progress.set_message("Accepting oracle ownership...");
+      oracle.accept_ownership()
+    } else {
+      let new_owner = self
+        .new_owner
+        .ok_or_else(|| anyhow!("a new owner is required unless accepting"))?;
+      progress.set_message("Proposing new oracle owner...");
+      oracle.transfer_ownership(new_owner)
+    };
+
+    match result {
+      Ok(txhash) => {
+        progress.finish_and_clear();
+        println!("Oracle {} ownership: ", self.oracle);
+        println!("  - address: {}", &oracle.id());
+        println!("  - owner: {}", &oracle.owner()?);
+        if let Some(pending) = oracle.pending_owner()? {
+          println!("  - pending owner: {}", &pending);
+        }
+        println!("  - tx: {}", &txhash);
+        Ok(())
+      }
+      Err(e) => {
+        error!("Failed to transfer oracle: {:?}", e);
+        Err(anyhow!(e))
+      }
+    }
+  }
+}
@@ -10,7 +10,14 @@ use tracing::{debug, error};
 #[derive(Debug, Clone, StructOpt)]
 pub(crate) struct UpdateCommand {
   oracle: String,
-  feed_value: Option<u128>,
+  #[structopt(allow_hyphen_values = true)]
+  feed_value: Option<i128>,
+  #[structopt(
+    long,
+    short,
+    about = "Uncertainty of the value, using the same decimals"
+  )]
+  confidence: Option<u128>,
 }
 
 impl UpdateCommand {
@@ -29,7 +36,7 @@ impl UpdateCommand {
     progress.enable_steady_tick(50);
     progress.set_message("Updating oracle...");
 
-    match oracle.update_value(self.feed_value) {
+    match oracle.update_value(self.feed_value, self.confidence) {
       Ok(txhash) => {
         let new_value = oracle.latest_snapshot()?;
         progress.finish_and_clear();
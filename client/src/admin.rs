@@ -0,0 +1,196 @@
+use anchor_client::{
+  solana_sdk::{
+    bpf_loader_upgradeable, commitment_config::CommitmentConfig,
+    pubkey::Pubkey, signature::Keypair, signature::Signature, system_program,
+  },
+  Client, ClientError, Cluster, Program,
+};
+use vvtec::state::{Aggregator, Config, Derivation, Metadata, Oracle};
+use std::rc::Rc;
+
+use crate::{config_address, Network};
+
+/// Manages the protocol-wide settings of the program: its admin, the pause
+/// switch, the fees charged for creating, updating and requesting feeds and
+/// the reaping of abandoned feeds.
+pub struct Admin {
+  program: Program,
+}
+
+impl Admin {
+  /// Opens the config on `network`, `payer` signs all the transactions.
+  pub fn open_on_network(network: Network, payer: Keypair) -> Self {
+    let client = Client::new_with_options(
+      Cluster::Custom(network.rpc_url(), network.ws_url()),
+      Rc::new(payer),
+      CommitmentConfig::processed(),
+    );
+    Self {
+      program: client.program(vvtec::id()),
+    }
+  }
+
+  /// Creates the config account, the payer becomes the admin. This must be
+  /// signed by the upgrade authority of the program.
+  pub fn init(
+    &self,
+    treasury: Pubkey,
+    create_fee: u64,
+    update_fee: u64,
+    request_fee: u64,
+  ) -> Result<Signature, ClientError> {
+    let program_data = Pubkey::find_program_address(
+      &[vvtec::id().as_ref()],
+      &bpf_loader_upgradeable::id(),
+    )
+    .0;
+
+    self
+      .program
+      .request()
+      .accounts(vvtec::accounts::InitConfig {
+        authority: self.program.payer(),
+        config: config_address(),
+        program: vvtec::id(),
+        program_data,
+        system_program: system_program::ID,
+      })
+      .args(vvtec::instruction::InitConfig {
+        treasury,
+        create_fee,
+        update_fee,
+        request_fee,
+      })
+      .send()
+  }
+
+  pub fn config(&self) -> Result<Config, ClientError> {
+    self.program.account(config_address())
+  }
+
+  /// Hands the admin role over to `admin`.
+  pub fn set_admin(&self, admin: Pubkey) -> Result<Signature, ClientError> {
+    self
+      .program
+      .request()
+      .accounts(self.configure())
+      .args(vvtec::instruction::SetAdmin { admin })
+      .send()
+  }
+
+  /// Stops all feeds from being created or written until [`Admin::unpause`]
+  /// is called.
+  pub fn pause(&self) -> Result<Signature, ClientError> {
+    self.set_paused(true)
+  }
+
+  pub fn unpause(&self) -> Result<Signature, ClientError> {
+    self.set_paused(false)
+  }
+
+  /// Sets the fees, in lamports, and the account they are paid to.
+  pub fn set_fees(
+    &self,
+    treasury: Pubkey,
+    create_fee: u64,
+    update_fee: u64,
+    request_fee: u64,
+  ) -> Result<Signature, ClientError> {
+    self
+      .program
+      .request()
+      .accounts(self.configure())
+      .args(vvtec::instruction::SetFees {
+        treasury,
+        create_fee,
+        update_fee,
+        request_fee,
+      })
+      .send()
+  }
+
+  /// Lets root feeds not written for `reap_after_secs` seconds be closed
+  /// with [`Admin::reap`], zero disables reaping.
+  pub fn set_reap_after(
+    &self,
+    reap_after_secs: i64,
+  ) -> Result<Signature, ClientError> {
+    self
+      .program
+      .request()
+      .accounts(self.configure())
+      .args(vvtec::instruction::SetReapAfter { reap_after_secs })
+      .send()
+  }
+
+  /// Restricts creating feeds in the global namespace to the admin while
+  /// `curated` is set.
+  pub fn set_curated(&self, curated: bool) -> Result<Signature, ClientError> {
+    self
+      .program
+      .request()
+      .accounts(self.configure())
+      .args(vvtec::instruction::SetCurated { curated })
+      .send()
+  }
+
+  /// Closes the abandoned feed `oracle` along with its metadata, aggregator
+  /// and derivation accounts, their rent goes to the treasury. Child feeds
+  /// are detached from their parent, feeds with children can be reaped once
+  /// their children are.
+  pub fn reap(&self, oracle: Pubkey) -> Result<Signature, ClientError> {
+    let feed: Oracle = self.program.account(oracle)?;
+    self
+      .program
+      .request()
+      .accounts(vvtec::accounts::Reap {
+        admin: self.program.payer(),
+        oracle,
+        parent: Some(feed.parent).filter(|_| !feed.is_root()),
+        metadata: Pubkey::find_program_address(
+          &[Metadata::SEED, oracle.as_ref()],
+          &vvtec::id(),
+        )
+        .0,
+        aggregator: self.companion(Aggregator::SEED, &oracle)?,
+        derivation: self.companion(Derivation::SEED, &oracle)?,
+        config: config_address(),
+        treasury: self.config()?.treasury,
+      })
+      .args(vvtec::instruction::Reap {})
+      .send()
+  }
+
+  /// The address of the account derived from `seed` and `oracle`, or `None`
+  /// if it does not exist.
+  fn companion(
+    &self,
+    seed: &[u8],
+    oracle: &Pubkey,
+  ) -> Result<Option<Pubkey>, ClientError> {
+    let address =
+      Pubkey::find_program_address(&[seed, oracle.as_ref()], &vvtec::id()).0;
+    let account = self
+      .program
+      .rpc()
+      .get_account_with_commitment(&address, CommitmentConfig::processed())?
+      .value;
+    Ok(account.map(|_| address))
+  }
+
+  fn set_paused(&self, paused: bool) -> Result<Signature, ClientError> {
+    self
+      .program
+      .request()
+      .accounts(self.configure())
+      .args(vvtec::instruction::SetPaused { paused })
+      .send()
+  }
+
+  fn configure(&self) -> vvtec::accounts::Configure {
+    vvtec::accounts::Configure {
+      admin: self.program.payer(),
+      config: config_address(),
+    }
+  }
+}
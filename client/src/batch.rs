@@ -0,0 +1,184 @@
+use anchor_client::{
+  anchor_lang::{InstructionData, ToAccountMetas},
+  solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::{AccountMeta, Instruction},
+    packet::PACKET_DATA_SIZE,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    system_program,
+    transaction::Transaction,
+  },
+  Client, ClientError, Cluster, Program,
+};
+use vvtec::FeedUpdate;
+use std::rc::Rc;
+
+use crate::{config_address, treasury, Network, OracleId};
+
+/// Writes the values of many oracles owned by the payer with as few
+/// transactions as possible.
+///
+/// Updates are packed into `batch_update` instructions in the order they were
+/// added, each transaction carries as many of them as fit. Updates within a
+/// single transaction land in the same slot, consistency across transactions
+/// is not guaranteed.
+pub struct Batch {
+  program: Program,
+  updates: Vec<(OracleId, FeedUpdate)>,
+}
+
+impl Batch {
+  pub fn open_on_network(network: Network, payer: Keypair) -> Self {
+    let client = Client::new_with_options(
+      Cluster::Custom(network.rpc_url(), network.ws_url()),
+      Rc::new(payer),
+      CommitmentConfig::processed(),
+    );
+    Self {
+      program: client.program(vvtec::id()),
+      updates: Vec::new(),
+    }
+  }
+
+  pub fn add(
+    &mut self,
+    oracle: OracleId,
+    value: Option<i128>,
+    confidence: Option<u128>,
+  ) -> &mut Self {
+    self
+      .updates
+      .push((oracle, FeedUpdate { value, confidence }));
+    self
+  }
+
+  pub fn len(&self) -> usize {
+    self.updates.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.updates.is_empty()
+  }
+
+  /// Sends all the added updates and clears the batch, returning the
+  /// signature of every transaction sent.
+  pub fn send(&mut self) -> Result<Vec<Signature>, ClientError> {
+    let treasury = treasury(&self.program)?;
+    let updates = std::mem::take(&mut self.updates);
+    self
+      .pack(&updates, treasury)
+      .into_iter()
+      .map(|ix| self.program.request().instruction(ix).send())
+      .collect()
+  }
+
+  /// Splits `updates` into the fewest instructions that each fit into a
+  /// single transaction.
+  fn pack(
+    &self,
+    updates: &[(OracleId, FeedUpdate)],
+    treasury: Pubkey,
+  ) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    let mut start = 0;
+    for end in 1..=updates.len() {
+      if end - start > 1
+        && self.tx_size(&updates[start..end], treasury) > PACKET_DATA_SIZE
+      {
+        instructions.push(self.instruction(&updates[start..end - 1], treasury));
+        start = end - 1;
+      }
+    }
+    if start < updates.len() {
+      instructions.push(self.instruction(&updates[start..], treasury));
+    }
+    instructions
+  }
+
+  fn tx_size(
+    &self,
+    updates: &[(OracleId, FeedUpdate)],
+    treasury: Pubkey,
+  ) -> usize {
+    let payer = self.program.payer();
+    let tx = Transaction::new_with_payer(
+      &[self.instruction(updates, treasury)],
+      Some(&payer),
+    );
+    // compact-u16 length prefix followed by the payer signature
+    1 + 64 + tx.message_data().len()
+  }
+
+  fn instruction(
+    &self,
+    updates: &[(OracleId, FeedUpdate)],
+    treasury: Pubkey,
+  ) -> Instruction {
+    let mut accounts = vvtec::accounts::BatchUpdate {
+      owner: self.program.payer(),
+      config: config_address(),
+      treasury,
+      system_program: system_program::ID,
+    }
+    .to_account_metas(None);
+    accounts.extend(
+      updates
+        .iter()
+        .map(|(oracle, _)| AccountMeta::new(oracle.0, false)),
+    );
+
+    Instruction {
+      program_id: vvtec::id(),
+      accounts,
+      data: vvtec::instruction::BatchUpdate {
+        updates: updates.iter().map(|(_, u)| u.clone()).collect(),
+      }
+      .data(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn packs_updates_into_packets() {
+    let batch = Batch::open_on_network(Network::Devnet, Keypair::new());
+    let treasury = Pubkey::new_unique();
+    let updates: Vec<_> = (0..64)
+      .map(|i| {
+        let update = FeedUpdate {
+          value: Some(i),
+          confidence: (i % 2 == 0).then_some(i as u128),
+        };
+        (OracleId(Pubkey::new_unique()), update)
+      })
+      .collect();
+
+    let instructions = batch.pack(&updates, treasury);
+    assert!(instructions.len() > 1);
+
+    // every instruction fits and takes as many updates as possible
+    let mut start = 0;
+    for (i, instruction) in instructions.iter().enumerate() {
+      let end = start + instruction.accounts.len() - 4;
+      let oracles = &instruction.accounts[4..];
+      assert!(oracles
+        .iter()
+        .zip(&updates[start..end])
+        .all(|(meta, (oracle, _))| meta.pubkey == oracle.0));
+      assert!(
+        batch.tx_size(&updates[start..end], treasury) <= PACKET_DATA_SIZE
+      );
+      if i + 1 < instructions.len() {
+        assert!(
+          batch.tx_size(&updates[start..=end], treasury) > PACKET_DATA_SIZE
+        );
+      }
+      start = end;
+    }
+    assert_eq!(start, updates.len());
+  }
+}
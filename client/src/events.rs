@@ -0,0 +1,145 @@
+use anchor_client::anchor_lang::{AnchorDeserialize, Discriminator};
+use vvtec::events::{
+  OracleCreated, OracleDeleted, OracleUpdated, RequestFulfilled, RequestOpened,
+};
+
+const PROGRAM_DATA: &str = "Program data: ";
+
+/// An event emitted by the vvtec program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OracleEvent {
+  Created(OracleCreated),
+  Updated(OracleUpdated),
+  Deleted(OracleDeleted),
+  RequestOpened(RequestOpened),
+  RequestFulfilled(RequestFulfilled),
+}
+
+impl OracleEvent {
+  /// Decodes a single event from its serialized form, including the
+  /// discriminator. Returns `None` for data that is not a vvtec event.
+  pub fn from_bytes(data: &[u8]) -> Option<Self> {
+    if data.len() < 8 {
+      return None;
+    }
+    let (discriminator, mut payload) = data.split_at(8);
+    if discriminator == OracleCreated::discriminator() {
+      OracleCreated::deserialize(&mut payload)
+        .ok()
+        .map(OracleEvent::Created)
+    } else if discriminator == OracleUpdated::discriminator() {
+      OracleUpdated::deserialize(&mut payload)
+        .ok()
+        .map(OracleEvent::Updated)
+    } else if discriminator == OracleDeleted::discriminator() {
+      OracleDeleted::deserialize(&mut payload)
+        .ok()
+        .map(OracleEvent::Deleted)
+    } else if discriminator == RequestOpened::discriminator() {
+      RequestOpened::deserialize(&mut payload)
+        .ok()
+        .map(OracleEvent::RequestOpened)
+    } else if discriminator == RequestFulfilled::discriminator() {
+      RequestFulfilled::deserialize(&mut payload)
+        .ok()
+        .map(OracleEvent::RequestFulfilled)
+    } else {
+      None
+    }
+  }
+}
+
+/// Extracts the events emitted by the vvtec program from the log messages of
+/// a transaction.
+///
+/// Only data logged while the vvtec program is executing is considered, so
+/// events of other programs invoked in the same transaction are skipped.
+pub fn decode_logs<S: AsRef<str>>(logs: &[S]) -> Vec<OracleEvent> {
+  let program_id = vvtec::id().to_string();
+  let invoke = format!("Program {} invoke", program_id);
+  let mut call_stack: Vec<bool> = Vec::new();
+  let mut events = Vec::new();
+
+  for log in logs.iter().map(AsRef::as_ref) {
+    if log.starts_with("Program ") && log.contains(" invoke [") {
+      call_stack.push(log.starts_with(&invoke));
+    } else if log.starts_with("Program ")
+      && (log.ends_with(" success") || log.contains(" failed: "))
+    {
+      call_stack.pop();
+    } else if let Some(data) = log.strip_prefix(PROGRAM_DATA) {
+      if call_stack.last() == Some(&true) {
+        if let Some(event) = base64::decode(data)
+          .ok()
+          .and_then(|bytes| OracleEvent::from_bytes(&bytes))
+        {
+          events.push(event);
+        }
+      }
+    }
+  }
+
+  events
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_client::{
+    anchor_lang::AnchorSerialize, solana_sdk::pubkey::Pubkey,
+  };
+
+  use super::*;
+
+  fn data_log<E: AnchorSerialize + Discriminator>(event: &E) -> String {
+    let mut data = E::discriminator().to_vec();
+    data.extend(event.try_to_vec().unwrap());
+    format!("{}{}", PROGRAM_DATA, base64::encode(data))
+  }
+
+  #[test]
+  fn decodes_events_of_vvtec_program() {
+    let updated = OracleUpdated {
+      oracle: Pubkey::new_unique(),
+      old_value: Some(1),
+      new_value: Some(-2),
+      old_timestamp: 10,
+      new_timestamp: 20,
+      slot: 5,
+      round_id: 2,
+    };
+    let deleted = OracleDeleted {
+      oracle: Pubkey::new_unique(),
+      owner: Pubkey::new_unique(),
+    };
+    let other = Pubkey::new_unique();
+    let logs = vec![
+      format!("Program {} invoke [1]", vvtec::id()),
+      "Program log: Instruction: Update".to_owned(),
+      data_log(&updated),
+      format!("Program {} invoke [2]", other),
+      data_log(&deleted),
+      format!("Program {} success", other),
+      data_log(&deleted),
+      format!("Program {} success", vvtec::id()),
+    ];
+
+    assert_eq!(
+      decode_logs(&logs),
+      vec![
+        OracleEvent::Updated(updated),
+        OracleEvent::Deleted(deleted.clone()),
+      ]
+    );
+  }
+
+  #[test]
+  fn ignores_unknown_data() {
+    let logs = vec![
+      format!("Program {} invoke [1]", vvtec::id()),
+      format!("{}{}", PROGRAM_DATA, base64::encode([0u8; 12])),
+      format!("Program {} success", vvtec::id()),
+    ];
+
+    assert!(decode_logs(&logs).is_empty());
+  }
+}
@@ -1,23 +1,41 @@
 use anchor_client::{
+  anchor_lang::error::ErrorCode,
   solana_sdk::pubkey::Pubkey,
   solana_sdk::{
+    account::from_account,
+    clock::Clock,
     commitment_config::CommitmentConfig,
+    instruction::AccountMeta,
     signature::{Keypair, Signature, Signer},
-    system_program,
+    system_program, sysvar,
   },
   Client, ClientError, Cluster, Program,
 };
 use chrono::NaiveDateTime;
-use vvtec::state::{Oracle as OracleState, Timestamp};
+use vvtec::message::PriceMessage;
+use vvtec::state::{
+  find_path_address, name_seed, Aggregator, Config, Derivation, Expression,
+  FeedMetadata, Metadata, Observation, Oracle as OracleState, Submission,
+  Timestamp, UpdatePolicy,
+};
 use vvtec::Feed;
 use vvtec::Result as VvtecResult;
 use std::{
+  convert::TryFrom,
   fmt::{Display, Formatter},
   rc::Rc,
   str::FromStr,
 };
 use tracing::debug;
 
+use crate::signed::SignedValue;
+
+pub mod admin;
+pub mod batch;
+pub mod events;
+pub mod requests;
+pub mod signed;
+
 pub enum Network {
   Devnet,
   Testnet,
@@ -26,23 +44,113 @@ pub enum Network {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Snapshot(pub Option<u128>, pub Timestamp);
+pub struct Snapshot {
+  pub value: Option<i128>,
+
+  /// The uncertainty of `value`, expressed with the same exponent.
+  pub confidence: Option<u128>,
+
+  /// The decimal exponent of `value`, the value represented by the snapshot
+  /// is `value * 10^exponent`.
+  pub exponent: i8,
+
+  /// The update schedule of the feed the snapshot was taken from.
+  pub policy: UpdatePolicy,
+
+  pub updated_at: Timestamp,
+
+  /// The slot the value was written in.
+  pub slot: u64,
+
+  /// The update counter of the feed at the time the value was written.
+  /// Consecutive values of a feed have consecutive round ids, so a gap
+  /// between two snapshots means updates were missed.
+  ///
+  /// Zero if unknown, as for history entries recorded before the history
+  /// kept round ids.
+  pub round_id: u64,
+}
+
+impl Snapshot {
+  /// The number of seconds by which the feed is overdue for an update as of
+  /// the unix timestamp `now`, or `None` if it is within its heartbeat.
+  ///
+  /// Feeds without a heartbeat never become stale.
+  pub fn staleness(&self, now: Timestamp) -> Option<i64> {
+    let heartbeat = self.policy.heartbeat_secs as i64;
+    let overdue = now.saturating_sub(self.updated_at) - heartbeat;
+    match heartbeat {
+      0 => None,
+      _ if overdue > 0 => Some(overdue),
+      _ => None,
+    }
+  }
+
+  pub fn is_stale(&self, now: Timestamp) -> bool {
+    self.staleness(now).is_some()
+  }
+
+  /// Returns true if `value` should be written to the feed as of `now`,
+  /// either because it moved past the deviation threshold of the feed or
+  /// because its heartbeat is due.
+  pub fn needs_update(&self, value: i128, now: Timestamp) -> bool {
+    let heartbeat = self.policy.heartbeat_secs as i64;
+    match self.value {
+      None => true,
+      Some(current) => {
+        self.policy.deviates(current, value)
+          || (heartbeat != 0
+            && now.saturating_sub(self.updated_at) >= heartbeat)
+      }
+    }
+  }
+
+  /// The value with the exponent applied, rendered as a decimal number.
+  pub fn scaled_value(&self) -> Option<String> {
+    self.value.map(|v| scale(v, self.exponent))
+  }
+
+  /// The confidence with the exponent applied, rendered as a decimal number.
+  pub fn scaled_confidence(&self) -> Option<String> {
+    self
+      .confidence
+      .map(|c| scale(i128::try_from(c).unwrap_or(i128::MAX), self.exponent))
+  }
+}
 
 impl Display for Snapshot {
   fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-    let value_fmt = match &self.0 {
-      None => "<null>".to_owned(),
-      Some(v) => v.to_string(),
-    };
+    let mut value_fmt = self
+      .scaled_value()
+      .unwrap_or_else(|| "<null>".to_owned());
+    if let Some(confidence) = self.scaled_confidence() {
+      value_fmt = format!("{} ± {}", value_fmt, confidence);
+    }
     write!(
       f,
       "{} @ {}",
       value_fmt,
-      NaiveDateTime::from_timestamp(self.1 as i64, 0)
+      NaiveDateTime::from_timestamp(self.updated_at, 0)
     )
   }
 }
 
+fn scale(value: i128, exponent: i8) -> String {
+  let sign = if value < 0 { "-" } else { "" };
+  let digits = value.unsigned_abs().to_string();
+  if exponent >= 0 {
+    return match value {
+      0 => digits,
+      _ => format!("{}{}{}", sign, digits, "0".repeat(exponent as usize)),
+    };
+  }
+
+  let decimals = exponent.unsigned_abs() as usize;
+  let padded = format!("{:0>width$}", digits, width = decimals + 1);
+  let (integer, fraction) = padded.split_at(padded.len() - decimals);
+  format!("{}{}.{}", sign, integer, fraction)
+}
+
 impl Network {
   fn rpc_url(&self) -> String {
     match self {
@@ -78,17 +186,76 @@ impl OracleId {
   pub fn from_address(address: Pubkey) -> Self {
     OracleId(address)
   }
+
+  /// Resolves an oracle by its path in the global namespace of the feeds
+  /// tree.
+  ///
+  /// Path segments are separated by dots, so `crypto.sol.usdt` is the feed
+  /// `usdt` attached to `sol`, which in turn is attached to the root feed
+  /// `crypto`.
   pub fn from_name(name: &str) -> Self {
-    let mut name_bytes: [u8; 32] = Default::default();
-    name_bytes[..name.len()].copy_from_slice(&name.as_bytes());
-    OracleId(Pubkey::find_program_address(&[&name_bytes], &vvtec::id()).0)
+    Self::resolve(&[], name)
+  }
+
+  /// Resolves an oracle by its path in the namespace of `owner`, that is the
+  /// account that created its root feed. Paths are dotted as in
+  /// [`OracleId::from_name`].
+  pub fn from_owner_and_name(owner: &Pubkey, name: &str) -> Self {
+    Self::resolve(&[OracleState::SEED, owner.as_ref()], name)
   }
+
+  /// Resolves a dotted path whose root feed is derived from `scope` followed
+  /// by its name.
+  fn resolve(scope: &[&[u8]], path: &str) -> Self {
+    OracleId(find_path_address(scope, path))
+  }
+}
+
+/// The time-weighted average of `history`, ordered from the oldest to the most
+/// recent snapshot, between the unix timestamps `from` and `to`. Returns `None`
+/// unless the history reaches back to `from`.
+fn history_twap(
+  history: &[Snapshot],
+  from: Timestamp,
+  to: Timestamp,
+) -> Option<i128> {
+  if to <= from || history.first()?.updated_at > from {
+    return None;
+  }
+  let mut sum = 0i128;
+  for (i, snapshot) in history.iter().enumerate() {
+    let start = snapshot.updated_at.max(from);
+    let end = history.get(i + 1).map_or(to, |next| next.updated_at).min(to);
+    if end > start {
+      let held = (end - start) as i128;
+      sum = sum.checked_add(snapshot.value.unwrap_or(0).checked_mul(held)?)?;
+    }
+  }
+  Some(sum / (to - from) as i128)
+}
+
+/// The address of the protocol-wide config account.
+fn config_address() -> Pubkey {
+  Pubkey::find_program_address(&[Config::SEED], &vvtec::id()).0
+}
+
+/// The account fees are currently paid to.
+fn treasury(program: &Program) -> Result<Pubkey, ClientError> {
+  let config: Config = program.account(config_address())?;
+  Ok(config.treasury)
 }
 
 impl FromStr for OracleId {
   type Err = vvtec::Error;
 
+  /// Parses an oracle address, an `owner/name` path in the namespace of
+  /// `owner` or a path in the global namespace.
   fn from_str(s: &str) -> VvtecResult<Self> {
+    if let Some((owner, name)) = s.split_once('/') {
+      let owner =
+        Pubkey::from_str(owner).map_err(|_| vvtec::Error::InvalidOracle)?;
+      return Ok(OracleId::from_owner_and_name(&owner, name));
+    }
     Ok(match Pubkey::from_str(s) {
       Ok(pubkey) => OracleId::from_address(pubkey),
       Err(_) => OracleId::from_name(s),
@@ -114,8 +281,8 @@ impl Oracle {
   }
 
   #[cfg(not(debug_assertions))]
-  pub fn open(id: OracleId) -> Result<Self> {
-    Oracle::open_on_network(id, Network::Mainnet)
+  pub fn open(id: OracleId, payer: Keypair) -> Result<Self, ClientError> {
+    Oracle::open_on_network(id, Network::Mainnet, payer)
   }
 
   pub fn open_on_network(
@@ -134,53 +301,37 @@ impl Oracle {
 
   #[cfg(debug_assertions)]
   pub fn create(
-    name: String,
-    owner: Pubkey,
+    feed: Feed,
     payer: Keypair,
-    initial_value: Option<u128>,
   ) -> Result<(Self, Signature), ClientError> {
-    Oracle::create_on_network(
-      name,
-      owner,
-      payer,
-      initial_value,
-      Network::Devnet,
-    )
+    Oracle::create_on_network(feed, payer, Network::Devnet)
   }
 
   #[cfg(not(debug_assertions))]
   pub fn create(
-    name: String,
-    owner: Pubkey,
+    feed: Feed,
     payer: Keypair,
-    balance: u64,
-    initial_value: Option<FeedValue>,
-  ) -> Result<(Self, Signature)> {
-    Oracle::create_on_network(
-      name,
-      owner,
-      payer,
-      balance,
-      initial_value,
-      Network::Mainnet,
-    )
+  ) -> Result<(Self, Signature), ClientError> {
+    Oracle::create_on_network(feed, payer, Network::Mainnet)
   }
 
+  /// Creates a new root feed in the global namespace, the payer must be the
+  /// admin when the config is curated.
   pub fn create_on_network(
-    name: String,
-    owner: Pubkey,
+    feed: Feed,
     payer: Keypair,
-    initial_value: Option<u128>,
     network: Network,
   ) -> Result<(Self, Signature), ClientError> {
-    assert!(name.len() > 2);
-    assert!(name.len() <= 32);
-    assert!(owner != Pubkey::default());
+    assert!(feed.name.len() > 2);
+    assert!(feed.name.len() <= OracleState::MAX_NAME_LEN);
+    assert!(
+      !feed.name.contains('.'),
+      "use create_child for nested feeds"
+    );
+    assert!(feed.history_capacity <= OracleState::MAX_HISTORY_CAPACITY);
+    assert!(feed.owner != Pubkey::default());
     assert!(payer.pubkey() != Pubkey::default());
 
-    let mut name_bytes: [u8; 32] = Default::default();
-    name_bytes[..name.len()].copy_from_slice(&name.as_bytes());
-
     let url = Cluster::Custom(network.rpc_url(), network.ws_url());
     let client = Client::new_with_options(
       url,
@@ -189,17 +340,12 @@ impl Oracle {
     );
     let program_acc = vvtec::id();
     let oracle_id =
-      Pubkey::find_program_address(&[&name_bytes], &program_acc).0;
-    let initial_feed = Feed {
-      owner,
-      name: name_bytes,
-      value: initial_value,
-    };
+      Pubkey::find_program_address(&[&name_seed(&feed.name)], &program_acc).0;
 
     debug!("Running on network: {}", &network);
     debug!("Using Vvtec program id {}", &program_acc);
     debug!("Derived Oracle address: {}", &oracle_id);
-    debug!("initial feed value for oracle: {:?}", &initial_feed);
+    debug!("initial feed value for oracle: {:?}", &feed);
 
     let program = client.program(program_acc);
 
@@ -208,9 +354,11 @@ impl Oracle {
       .accounts(vvtec::accounts::Create {
         payer: program.payer(),
         oracle: oracle_id,
+        config: config_address(),
+        treasury: treasury(&program)?,
         system_program: system_program::ID,
       })
-      .args(vvtec::instruction::Create { feed: initial_feed });
+      .args(vvtec::instruction::Create { feed });
 
     match req.send() {
       Ok(txhash) => Ok((
@@ -224,47 +372,708 @@ impl Oracle {
     }
   }
 
+  /// Creates a new root feed in the namespace of the payer, who must be the
+  /// owner of `feed`. Unlike the global namespace, which the admin can
+  /// curate, anyone can create feeds in their own namespace.
+  pub fn create_namespaced_on_network(
+    feed: Feed,
+    payer: Keypair,
+    network: Network,
+  ) -> Result<(Self, Signature), ClientError> {
+    assert!(!feed.name.is_empty());
+    assert!(feed.name.len() <= OracleState::MAX_NAME_LEN);
+    assert!(
+      !feed.name.contains('.'),
+      "use create_child for nested feeds"
+    );
+    assert!(feed.history_capacity <= OracleState::MAX_HISTORY_CAPACITY);
+    assert!(feed.owner == payer.pubkey());
+
+    let owner = feed.owner;
+    let oracle_id = OracleId::from_owner_and_name(&owner, &feed.name);
+    let client = Client::new_with_options(
+      Cluster::Custom(network.rpc_url(), network.ws_url()),
+      Rc::new(payer),
+      CommitmentConfig::processed(),
+    );
+
+    debug!("Running on network: {}", &network);
+    debug!("Derived namespaced oracle address: {}", &oracle_id);
+
+    let program = client.program(vvtec::id());
+    let txhash = program
+      .request()
+      .accounts(vvtec::accounts::CreateNamespaced {
+        payer: owner,
+        owner,
+        oracle: oracle_id.0,
+        config: config_address(),
+        treasury: treasury(&program)?,
+        system_program: system_program::ID,
+      })
+      .args(vvtec::instruction::CreateNamespaced { feed })
+      .send()?;
+
+    Ok((
+      Self {
+        id: oracle_id,
+        program,
+      },
+      txhash,
+    ))
+  }
+
+  /// Creates a new feed attached to the `parent` feed.
+  ///
+  /// The payer must be the owner of the parent feed, the new feed is owned
+  /// by the owner of `feed`.
+  pub fn create_child_on_network(
+    parent: OracleId,
+    feed: Feed,
+    payer: Keypair,
+    network: Network,
+  ) -> Result<(Self, Signature), ClientError> {
+    assert!(!feed.name.is_empty());
+    assert!(feed.name.len() <= OracleState::MAX_NAME_LEN);
+    assert!(!feed.name.contains('.'));
+    assert!(feed.history_capacity <= OracleState::MAX_HISTORY_CAPACITY);
+    assert!(feed.owner != Pubkey::default());
+
+    let client = Client::new_with_options(
+      Cluster::Custom(network.rpc_url(), network.ws_url()),
+      Rc::new(payer),
+      CommitmentConfig::processed(),
+    );
+    let child_id = Pubkey::find_program_address(
+      &[parent.0.as_ref(), &name_seed(&feed.name)],
+      &vvtec::id(),
+    )
+    .0;
+
+    debug!("Running on network: {}", &network);
+    debug!("Parent oracle address: {}", &parent);
+    debug!("Derived child oracle address: {}", &child_id);
+
+    let program = client.program(vvtec::id());
+    let txhash = program
+      .request()
+      .accounts(vvtec::accounts::AttachChild {
+        payer: program.payer(),
+        owner: program.payer(),
+        parent: parent.0,
+        child: child_id,
+        config: config_address(),
+        treasury: treasury(&program)?,
+        system_program: system_program::ID,
+      })
+      .args(vvtec::instruction::AttachChild { feed })
+      .send()?;
+
+    Ok((
+      Self {
+        id: OracleId::from_address(child_id),
+        program,
+      },
+      txhash,
+    ))
+  }
+
+  /// Opens the root feed `name` of the native build of the program deployed
+  /// at `program_id`.
+  pub fn open_native_on_network(
+    program_id: Pubkey,
+    name: &str,
+    network: Network,
+    payer: Keypair,
+  ) -> Result<Self, ClientError> {
+    let client = Client::new_with_options(
+      Cluster::Custom(network.rpc_url(), network.ws_url()),
+      Rc::new(payer),
+      CommitmentConfig::processed(),
+    );
+    Ok(Self {
+      id: OracleId::from_address(vvtec::native::instruction::oracle_address(
+        &program_id,
+        name,
+      )),
+      program: client.program(program_id),
+    })
+  }
+
+  /// Creates a new root feed with the native build of the program deployed
+  /// at `program_id`. Unlike the anchor program, it charges no fees and any
+  /// payer may create feeds.
+  pub fn create_native_on_network(
+    program_id: Pubkey,
+    feed: Feed,
+    payer: Keypair,
+    network: Network,
+  ) -> Result<(Self, Signature), ClientError> {
+    assert!(!feed.name.is_empty());
+    assert!(feed.name.len() <= OracleState::MAX_NAME_LEN);
+    assert!(
+      !feed.name.contains('.'),
+      "the native program has no nested feeds"
+    );
+    assert!(feed.history_capacity <= OracleState::MAX_HISTORY_CAPACITY);
+    assert!(feed.owner != Pubkey::default());
+
+    debug!("Running on network: {}", &network);
+    debug!("Using native Vvtec program id {}", &program_id);
+
+    let oracle =
+      Self::open_native_on_network(program_id, &feed.name, network, payer)?;
+
+    debug!("Derived Oracle address: {}", &oracle.id);
+
+    let txhash = oracle
+      .program
+      .request()
+      .instruction(vvtec::native::instruction::create(
+        &program_id,
+        &oracle.program.payer(),
+        feed,
+      ))
+      .send()?;
+
+    Ok((oracle, txhash))
+  }
+
   pub fn id(&self) -> OracleId {
     self.id
   }
 
+  /// Returns true if this oracle belongs to the native build of the program
+  /// rather than the anchor program. Oracles of both builds are read the same
+  /// way, but the native build only supports updates, ownership transfers
+  /// and deletion of root feeds in the global namespace.
+  pub fn is_native(&self) -> bool {
+    self.program.id() != vvtec::id()
+  }
+
+  /// The feed this oracle is attached to, or `None` for root feeds.
+  pub fn parent(&self) -> Result<Option<OracleId>, ClientError> {
+    let feed = self.state()?;
+    Ok(match feed.is_root() {
+      true => None,
+      false => Some(OracleId::from_address(feed.parent)),
+    })
+  }
+
   pub fn latest_snapshot(&self) -> Result<Snapshot, ClientError> {
-    let feed: OracleState = self.program.account(self.id.0)?;
-    Ok(Snapshot(feed.value, feed.updated_at))
+    let feed = self.state()?;
+    Ok(Snapshot {
+      value: feed.value,
+      confidence: feed.confidence,
+      exponent: feed.exponent,
+      policy: feed.policy,
+      updated_at: feed.updated_at,
+      slot: feed.slot,
+      round_id: feed.round_id,
+    })
+  }
+
+  /// Returns the values retained in the on-chain history of this oracle,
+  /// ordered from the oldest to the most recent one.
+  pub fn history(&self) -> Result<Vec<Snapshot>, ClientError> {
+    let feed = self.state()?;
+    Ok(
+      feed
+        .history()
+        .map(|entry| Snapshot {
+          value: entry.value,
+          confidence: entry.confidence,
+          exponent: feed.exponent,
+          policy: feed.policy,
+          updated_at: entry.updated_at,
+          slot: entry.slot,
+          round_id: entry.round_id,
+        })
+        .collect(),
+    )
+  }
+
+  /// Reads the value accumulator of this oracle as of the current cluster
+  /// time. The time-weighted average value between two observations is given
+  /// by [`Observation::twap_since`].
+  pub fn observe(&self) -> Result<Observation, ClientError> {
+    let feed = self.state()?;
+    Ok(feed.observe(self.cluster_time()?))
+  }
+
+  /// The time-weighted average value of this oracle over the last `window`
+  /// seconds, computed from its on-chain history.
+  ///
+  /// Returns `None` if the history does not reach back that far, e.g. because
+  /// the oracle retains no history. Take two [`Oracle::observe`] readings
+  /// `window` seconds apart instead.
+  pub fn twap(&self, window: i64) -> Result<Option<i128>, ClientError> {
+    let now = self.cluster_time()?;
+    Ok(history_twap(&self.history()?, now.saturating_sub(window), now))
+  }
+
+  /// The unix timestamp of the cluster clock.
+  fn cluster_time(&self) -> Result<Timestamp, ClientError> {
+    let account = self.program.rpc().get_account(&sysvar::clock::ID)?;
+    let clock: Clock = from_account(&account).ok_or_else(|| {
+      ClientError::AnchorError(ErrorCode::AccountDidNotDeserialize.into())
+    })?;
+    Ok(clock.unix_timestamp)
   }
 
   pub fn owner(&self) -> Result<Pubkey, ClientError> {
-    let feed: OracleState = self.program.account(self.id.0)?;
+    let feed = self.state()?;
     Ok(feed.owner)
   }
 
   pub fn name(&self) -> Result<String, ClientError> {
-    let feed: OracleState = self.program.account(self.id.0)?;
-    Ok(String::from_utf8_lossy(&feed.name).to_string())
+    let feed = self.state()?;
+    Ok(feed.name)
   }
 
+  /// Writes a new value to this oracle. To write many oracles at once, use a
+  /// [`batch::Batch`].
   pub fn update_value(
     &self,
-    value: Option<u128>,
+    value: Option<i128>,
+    confidence: Option<u128>,
   ) -> Result<Signature, ClientError> {
+    if self.is_native() {
+      return self
+        .program
+        .request()
+        .instruction(vvtec::native::instruction::update(
+          &self.program.id(),
+          &self.program.payer(),
+          &self.id.0,
+          value,
+          confidence,
+        ))
+        .send();
+    }
+
     self
       .program
       .request()
       .accounts(vvtec::accounts::Update {
         owner: self.program.payer(),
         oracle: self.id.0,
+        config: config_address(),
+        treasury: treasury(&self.program)?,
+        system_program: system_program::ID,
       })
-      .args(vvtec::instruction::Update { value })
+      .args(vvtec::instruction::Update { value, confidence })
       .send()
   }
 
+  /// Signs `value` as of the unix timestamp `timestamp` for this oracle.
+  /// Only signatures of the owner are accepted, the signed value can then be
+  /// submitted by anyone with [`Oracle::submit_signed`].
+  pub fn sign_value(
+    &self,
+    owner: &Keypair,
+    value: Option<i128>,
+    confidence: Option<u128>,
+    timestamp: Timestamp,
+  ) -> Result<SignedValue, ClientError> {
+    let feed = self.state()?;
+    Ok(SignedValue::sign(
+      owner,
+      PriceMessage {
+        name: feed.name_seed,
+        parent: feed.scope(),
+        value,
+        confidence,
+        timestamp,
+      },
+    ))
+  }
+
+  /// Writes a value signed by the owner of this oracle, the payer relays it
+  /// and pays the fees.
+  pub fn submit_signed(
+    &self,
+    signed: &SignedValue,
+  ) -> Result<Signature, ClientError> {
+    self
+      .program
+      .request()
+      .instruction(signed.verify_instruction())
+      .accounts(vvtec::accounts::SubmitSigned {
+        relayer: self.program.payer(),
+        oracle: self.id.0,
+        config: config_address(),
+        treasury: treasury(&self.program)?,
+        instructions: sysvar::instructions::ID,
+        system_program: system_program::ID,
+      })
+      .args(vvtec::instruction::SubmitSigned {
+        message: signed.message.clone(),
+      })
+      .send()
+  }
+
+  /// Proposes `new_owner` as the owner of this oracle. The ownership changes
+  /// once the proposed account calls [`Oracle::accept_ownership`].
+  pub fn transfer_ownership(
+    &self,
+    new_owner: Pubkey,
+  ) -> Result<Signature, ClientError> {
+    self.propose_owner(Some(new_owner))
+  }
+
+  /// Withdraws a pending ownership transfer of this oracle.
+  pub fn cancel_ownership_transfer(&self) -> Result<Signature, ClientError> {
+    self.propose_owner(None)
+  }
+
+  /// Accepts a pending ownership transfer, the payer becomes the owner of
+  /// this oracle.
+  pub fn accept_ownership(&self) -> Result<Signature, ClientError> {
+    if self.is_native() {
+      return self
+        .program
+        .request()
+        .instruction(vvtec::native::instruction::accept_owner(
+          &self.program.id(),
+          &self.program.payer(),
+          &self.id.0,
+        ))
+        .send();
+    }
+
+    self
+      .program
+      .request()
+      .accounts(vvtec::accounts::AcceptOwner {
+        new_owner: self.program.payer(),
+        oracle: self.id.0,
+      })
+      .args(vvtec::instruction::AcceptOwner {})
+      .send()
+  }
+
+  /// The account an ownership transfer was proposed to, if any.
+  pub fn pending_owner(&self) -> Result<Option<Pubkey>, ClientError> {
+    let feed = self.state()?;
+    Ok(feed.pending_owner)
+  }
+
+  fn propose_owner(
+    &self,
+    new_owner: Option<Pubkey>,
+  ) -> Result<Signature, ClientError> {
+    if self.is_native() {
+      return self
+        .program
+        .request()
+        .instruction(vvtec::native::instruction::propose_owner(
+          &self.program.id(),
+          &self.program.payer(),
+          &self.id.0,
+          new_owner,
+        ))
+        .send();
+    }
+
+    self
+      .program
+      .request()
+      .accounts(vvtec::accounts::ProposeOwner {
+        owner: self.program.payer(),
+        oracle: self.id.0,
+      })
+      .args(vvtec::instruction::ProposeOwner { new_owner })
+      .send()
+  }
+
+  /// Converts an oracle account created with an older layout to the current
+  /// one. The payer covers the additional rent. Histories longer than
+  /// [`OracleState::MAX_HISTORY_CAPACITY`] lose their oldest entries.
+  pub fn migrate(&self) -> Result<Signature, ClientError> {
+    self
+      .program
+      .request()
+      .accounts(vvtec::accounts::Migrate {
+        payer: self.program.payer(),
+        oracle: self.id.0,
+        system_program: system_program::ID,
+      })
+      .args(vvtec::instruction::Migrate {})
+      .send()
+  }
+
+  /// Fetches the oracle account, decoding any of its layout versions.
+  fn state(&self) -> Result<OracleState, ClientError> {
+    let data = self.program.rpc().get_account_data(&self.id.0)?;
+    OracleState::from_account_data(&data)
+      .map_err(|e| ClientError::AnchorError(e.into()))
+  }
+
+  /// Switches this oracle to aggregation mode. From then on its value is the
+  /// median of the fresh submissions of `publishers`, recomputed whenever at
+  /// least `quorum` of them submitted within the last `max_age_slots` slots.
+  pub fn init_aggregator(
+    &self,
+    publishers: Vec<Pubkey>,
+    quorum: u8,
+    max_age_slots: u64,
+  ) -> Result<Signature, ClientError> {
+    self
+      .program
+      .request()
+      .accounts(vvtec::accounts::InitAggregator {
+        owner: self.program.payer(),
+        oracle: self.id.0,
+        aggregator: self.aggregator_address(),
+        system_program: system_program::ID,
+      })
+      .args(vvtec::instruction::InitAggregator {
+        publishers,
+        quorum,
+        max_age_slots,
+      })
+      .send()
+  }
+
+  /// Submits a value as one of the publishers of an aggregated oracle.
+  pub fn submit(&self, value: i128) -> Result<Signature, ClientError> {
+    self
+      .program
+      .request()
+      .accounts(vvtec::accounts::Submit {
+        publisher: self.program.payer(),
+        oracle: self.id.0,
+        aggregator: self.aggregator_address(),
+        config: config_address(),
+      })
+      .args(vvtec::instruction::Submit { value })
+      .send()
+  }
+
+  /// The latest submissions of all publishers of an aggregated oracle.
+  pub fn submissions(&self) -> Result<Vec<Submission>, ClientError> {
+    let aggregator: Aggregator =
+      self.program.account(self.aggregator_address())?;
+    Ok(aggregator.submissions)
+  }
+
+  /// The latest submission of a single publisher of an aggregated oracle.
+  pub fn submission(
+    &self,
+    publisher: &Pubkey,
+  ) -> Result<Option<Submission>, ClientError> {
+    Ok(
+      self
+        .submissions()?
+        .into_iter()
+        .find(|s| &s.publisher == publisher),
+    )
+  }
+
+  /// Returns this oracle to direct mode, where the owner writes its value.
+  pub fn close_aggregator(&self) -> Result<Signature, ClientError> {
+    self
+      .program
+      .request()
+      .accounts(vvtec::accounts::CloseAggregator {
+        owner: self.program.payer(),
+        oracle: self.id.0,
+        aggregator: self.aggregator_address(),
+      })
+      .args(vvtec::instruction::CloseAggregator {})
+      .send()
+  }
+
+  fn aggregator_address(&self) -> Pubkey {
+    Pubkey::find_program_address(
+      &[Aggregator::SEED, self.id.0.as_ref()],
+      &vvtec::id(),
+    )
+    .0
+  }
+
+  /// Makes this oracle a derived feed whose value is computed from other
+  /// oracles by `expression`, see [`Oracle::refresh`].
+  pub fn init_derivation(
+    &self,
+    expression: Expression,
+  ) -> Result<Signature, ClientError> {
+    self
+      .program
+      .request()
+      .accounts(vvtec::accounts::InitDerivation {
+        owner: self.program.payer(),
+        oracle: self.id.0,
+        derivation: self.derivation_address(),
+        system_program: system_program::ID,
+      })
+      .args(vvtec::instruction::InitDerivation { expression })
+      .send()
+  }
+
+  /// The expression a derived oracle is computed with.
+  pub fn derivation(&self) -> Result<Expression, ClientError> {
+    let derivation: Derivation =
+      self.program.account(self.derivation_address())?;
+    Ok(derivation.expression)
+  }
+
+  /// Recomputes a derived oracle from the current values of its sources.
+  /// Anyone can refresh, but only after some source was updated.
+  pub fn refresh(&self) -> Result<Signature, ClientError> {
+    let sources = self
+      .derivation()?
+      .sources()
+      .into_iter()
+      .map(|source| AccountMeta::new_readonly(source, false))
+      .collect::<Vec<_>>();
+    self
+      .program
+      .request()
+      .accounts(vvtec::accounts::Refresh {
+        oracle: self.id.0,
+        derivation: self.derivation_address(),
+        config: config_address(),
+      })
+      .accounts(sources)
+      .args(vvtec::instruction::Refresh {})
+      .send()
+  }
+
+  /// Returns this oracle to direct mode, where the owner writes its value.
+  pub fn close_derivation(&self) -> Result<Signature, ClientError> {
+    self
+      .program
+      .request()
+      .accounts(vvtec::accounts::CloseDerivation {
+        owner: self.program.payer(),
+        oracle: self.id.0,
+        derivation: self.derivation_address(),
+      })
+      .args(vvtec::instruction::CloseDerivation {})
+      .send()
+  }
+
+  fn derivation_address(&self) -> Pubkey {
+    Pubkey::find_program_address(
+      &[Derivation::SEED, self.id.0.as_ref()],
+      &vvtec::id(),
+    )
+    .0
+  }
+
+  /// The descriptive metadata of this oracle, if the owner set any.
+  pub fn metadata(&self) -> Result<Option<FeedMetadata>, ClientError> {
+    match self.program.account::<Metadata>(self.metadata_address()) {
+      Ok(metadata) => Ok(Some(metadata.info)),
+      Err(ClientError::AccountNotFound) => Ok(None),
+      Err(e) => Err(e),
+    }
+  }
+
+  /// Sets the descriptive metadata of this oracle, replacing any previous
+  /// one. The payer must be the owner, and pays the rent of the metadata
+  /// account when it is first set.
+  pub fn set_metadata(
+    &self,
+    info: FeedMetadata,
+  ) -> Result<Signature, ClientError> {
+    if self.metadata()?.is_none() {
+      return self
+        .program
+        .request()
+        .accounts(vvtec::accounts::InitMetadata {
+          owner: self.program.payer(),
+          oracle: self.id.0,
+          metadata: self.metadata_address(),
+          system_program: system_program::ID,
+        })
+        .args(vvtec::instruction::InitMetadata { info })
+        .send();
+    }
+
+    self
+      .program
+      .request()
+      .accounts(vvtec::accounts::SetMetadata {
+        owner: self.program.payer(),
+        oracle: self.id.0,
+        metadata: self.metadata_address(),
+      })
+      .args(vvtec::instruction::SetMetadata { info })
+      .send()
+  }
+
+  /// Removes the metadata of this oracle and returns its rent to the owner.
+  pub fn close_metadata(&self) -> Result<Signature, ClientError> {
+    self
+      .program
+      .request()
+      .accounts(vvtec::accounts::CloseMetadata {
+        owner: self.program.payer(),
+        oracle: self.id.0,
+        metadata: self.metadata_address(),
+      })
+      .args(vvtec::instruction::CloseMetadata {})
+      .send()
+  }
+
+  fn metadata_address(&self) -> Pubkey {
+    Pubkey::find_program_address(
+      &[Metadata::SEED, self.id.0.as_ref()],
+      &vvtec::id(),
+    )
+    .0
+  }
+
+  /// Deletes this oracle and refunds its rent to the payer. Child feeds are
+  /// detached from their parent, which requires the signature of the parent
+  /// owner, and always refund their own owner.
   pub fn delete(&self) -> Result<Signature, ClientError> {
+    self.delete_to(self.program.payer())
+  }
+
+  /// Deletes this oracle like [`Oracle::delete`] and refunds its rent to
+  /// `recipient`, unless it is a child feed.
+  pub fn delete_to(
+    &self,
+    recipient: Pubkey,
+  ) -> Result<Signature, ClientError> {
+    if self.is_native() {
+      return self
+        .program
+        .request()
+        .instruction(vvtec::native::instruction::delete(
+          &self.program.id(),
+          &self.program.payer(),
+          &self.id.0,
+          &recipient,
+        ))
+        .send();
+    }
+
+    if let Some(parent) = self.parent()? {
+      return self
+        .program
+        .request()
+        .accounts(vvtec::accounts::DetachChild {
+          owner: self.program.payer(),
+          parent: parent.0,
+          child: self.id.0,
+          child_owner: self.owner()?,
+        })
+        .args(vvtec::instruction::DetachChild {})
+        .send();
+    }
+
     self
       .program
       .request()
       .accounts(vvtec::accounts::Delete {
         owner: self.program.payer(),
         oracle: self.id.0,
+        recipient,
       })
       .args(vvtec::instruction::Delete {})
       .send()
@@ -273,8 +1082,195 @@ impl Oracle {
 
 #[cfg(test)]
 mod tests {
+  use super::*;
+
   #[test]
   fn it_works() {
     assert_eq!(2 + 2, 4);
   }
+
+  #[test]
+  fn scales_values_by_exponent() {
+    assert_eq!(scale(12345, -2), "123.45");
+    assert_eq!(scale(-5, -3), "-0.005");
+    assert_eq!(scale(0, -2), "0.00");
+    assert_eq!(scale(7, 2), "700");
+    assert_eq!(scale(0, 2), "0");
+    assert_eq!(scale(-42, 0), "-42");
+  }
+
+  #[test]
+  fn displays_scaled_snapshot() {
+    let mut snapshot = Snapshot {
+      value: Some(-186),
+      confidence: None,
+      exponent: -2,
+      policy: UpdatePolicy::default(),
+      updated_at: 0,
+      slot: 0,
+      round_id: 0,
+    };
+    assert_eq!(snapshot.to_string(), "-1.86 @ 1970-01-01 00:00:00");
+
+    snapshot.confidence = Some(5);
+    assert_eq!(snapshot.to_string(), "-1.86 ± 0.05 @ 1970-01-01 00:00:00");
+  }
+
+  #[test]
+  fn reports_staleness_against_heartbeat() {
+    let mut snapshot = Snapshot {
+      value: Some(1),
+      confidence: None,
+      exponent: 0,
+      policy: UpdatePolicy::default(),
+      updated_at: 1_000,
+      slot: 0,
+      round_id: 0,
+    };
+    assert_eq!(snapshot.staleness(1_000_000), None);
+
+    snapshot.policy.heartbeat_secs = 60;
+    assert!(!snapshot.is_stale(1_060));
+    assert_eq!(snapshot.staleness(1_061), Some(1));
+    assert!(snapshot.is_stale(1_061));
+  }
+
+  #[test]
+  fn needs_update_on_deviation_or_heartbeat() {
+    let snapshot = Snapshot {
+      value: Some(10_000),
+      confidence: None,
+      exponent: 0,
+      policy: UpdatePolicy {
+        heartbeat_secs: 60,
+        deviation_bps: 50,
+      },
+      updated_at: 1_000,
+      slot: 0,
+      round_id: 0,
+    };
+    assert!(!snapshot.needs_update(10_050, 1_010));
+    assert!(snapshot.needs_update(10_051, 1_010));
+    assert!(snapshot.needs_update(9_949, 1_010));
+    assert!(snapshot.needs_update(10_000, 1_060));
+  }
+
+  #[test]
+  fn parses_namespaced_paths() {
+    let owner = Pubkey::new_unique();
+    let path = format!("{}/crypto.sol", owner);
+
+    let id = OracleId::from_str(&path).unwrap();
+    assert_eq!(id, OracleId::from_owner_and_name(&owner, "crypto.sol"));
+    assert_ne!(id, OracleId::from_name("crypto.sol"));
+    assert!(OracleId::from_str("not-a-key/crypto").is_err());
+  }
+
+  #[test]
+  fn builds_native_instructions() {
+    use anchor_client::anchor_lang::AnchorDeserialize;
+    use vvtec::native::{instruction, OracleInstruction};
+
+    let program_id = Pubkey::new_unique();
+    let payer = Pubkey::new_unique();
+    let feed = Feed {
+      owner: payer,
+      name: "crypto".to_owned(),
+      value: Some(7),
+      exponent: -2,
+      policy: UpdatePolicy::default(),
+      history_capacity: 0,
+    };
+    let oracle = instruction::oracle_address(&program_id, "crypto");
+
+    let create = instruction::create(&program_id, &payer, feed);
+    assert_eq!(create.program_id, program_id);
+    assert_eq!(create.accounts[0], AccountMeta::new(payer, true));
+    assert_eq!(create.accounts[1], AccountMeta::new(oracle, false));
+    assert!(matches!(
+      OracleInstruction::try_from_slice(&create.data),
+      Ok(OracleInstruction::Create { feed }) if feed.name == "crypto"
+    ));
+
+    let update =
+      instruction::update(&program_id, &payer, &oracle, Some(8), None);
+    assert_eq!(update.accounts[0], AccountMeta::new_readonly(payer, true));
+    assert_eq!(update.accounts[1], AccountMeta::new(oracle, false));
+
+    let recipient = Pubkey::new_unique();
+    let delete = instruction::delete(&program_id, &payer, &oracle, &recipient);
+    assert_eq!(delete.accounts[0], AccountMeta::new_readonly(payer, true));
+    assert_eq!(delete.accounts[2], AccountMeta::new(recipient, false));
+  }
+
+  #[test]
+  fn hashes_long_names_into_seeds() {
+    let short = "crypto";
+    let long = "a-feed-name-that-does-not-fit-into-thirty-two-bytes";
+    assert_eq!(&name_seed(short)[..short.len()], short.as_bytes());
+    assert!(name_seed(short)[short.len()..].iter().all(|b| *b == 0));
+    assert_ne!(&name_seed(long)[..], &long.as_bytes()[..32]);
+
+    let (expected, _) =
+      Pubkey::find_program_address(&[&name_seed(long)], &vvtec::id());
+    assert_eq!(OracleId::from_name(long), OracleId::from_address(expected));
+  }
+
+  #[test]
+  fn resolves_long_dotted_paths_through_children() {
+    let path = "equities.nasdaq.aapl.close.adjusted-for-splits-and-dividends";
+    let expected = path.split('.').skip(1).fold(
+      OracleId::from_name("equities").0,
+      |parent, segment| {
+        Pubkey::find_program_address(
+          &[parent.as_ref(), &name_seed(segment)],
+          &vvtec::id(),
+        )
+        .0
+      },
+    );
+    assert_eq!(OracleId::from_name(path), OracleId::from_address(expected));
+  }
+
+  #[test]
+  fn fits_largest_oracle_into_program_allocation() {
+    let space = OracleState::space(
+      OracleState::MAX_NAME_LEN,
+      OracleState::MAX_HISTORY_CAPACITY,
+    );
+    assert!(space <= 10 * 1024);
+  }
+
+  #[test]
+  fn derives_fast_oracle_discriminator() {
+    let hash = anchor_client::solana_sdk::hash::hash(b"account:FastOracle");
+    assert_eq!(
+      vvtec::state::FastOracle::DISCRIMINATOR,
+      hash.to_bytes()[..8]
+    );
+  }
+
+  #[test]
+  fn averages_history_over_time() {
+    let snapshot = |value, updated_at| Snapshot {
+      value,
+      confidence: None,
+      exponent: 0,
+      policy: UpdatePolicy::default(),
+      updated_at,
+      slot: 0,
+      round_id: 0,
+    };
+    let history = [
+      snapshot(Some(10), 100),
+      snapshot(Some(40), 120),
+      snapshot(None, 150),
+    ];
+
+    // 10 for 10s, then 40 for 30s, then nothing for 10s
+    assert_eq!(history_twap(&history, 110, 160), Some(1_300 / 50));
+    assert_eq!(history_twap(&history, 100, 120), Some(10));
+    assert_eq!(history_twap(&history, 90, 160), None);
+    assert_eq!(history_twap(&history, 120, 120), None);
+  }
 }
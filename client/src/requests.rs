@@ -0,0 +1,145 @@
+use anchor_client::{
+  solana_client::rpc_filter::{Memcmp, RpcFilterType},
+  solana_sdk::{
+    commitment_config::CommitmentConfig,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    system_program,
+  },
+  Client, ClientError, Cluster, Program,
+};
+use vvtec::state::{Config, Request};
+use std::rc::Rc;
+
+use crate::{config_address, Network, OracleId};
+
+/// Offset of [`Request::oracle`] in the account data.
+const ORACLE_OFFSET: usize = 8 + 8;
+
+/// Offset of the option tag of [`Request::fulfilled_at`] in the account data.
+const FULFILLED_OFFSET: usize = ORACLE_OFFSET + 32 + 32 + 8;
+
+/// The option tag of `None`.
+const NONE_TAG: u8 = 0;
+
+/// On-demand values of feeds.
+///
+/// Consumers open a request for a feed and read it once it is fulfilled,
+/// owners of feeds list the pending requests of their feeds and fulfill them.
+pub struct Requests {
+  program: Program,
+}
+
+impl Requests {
+  pub fn open_on_network(network: Network, payer: Keypair) -> Self {
+    let client = Client::new_with_options(
+      Cluster::Custom(network.rpc_url(), network.ws_url()),
+      Rc::new(payer),
+      CommitmentConfig::processed(),
+    );
+    Self {
+      program: client.program(vvtec::id()),
+    }
+  }
+
+  /// Requests a fresh value of `oracle`, the payer pays the request fee and
+  /// the rent of the request account. Returns the id of the request.
+  ///
+  /// Ids are assigned sequentially, so this fails if another request is
+  /// opened concurrently and takes the same id.
+  pub fn open(
+    &self,
+    oracle: OracleId,
+  ) -> Result<(u64, Signature), ClientError> {
+    let config: Config = self.program.account(config_address())?;
+    let id = config.next_request_id;
+    let txhash = self
+      .program
+      .request()
+      .accounts(vvtec::accounts::OpenRequest {
+        requester: self.program.payer(),
+        oracle: oracle.0,
+        request: request_address(id),
+        config: config_address(),
+        treasury: config.treasury,
+        system_program: system_program::ID,
+      })
+      .args(vvtec::instruction::OpenRequest {})
+      .send()?;
+    Ok((id, txhash))
+  }
+
+  pub fn get(&self, id: u64) -> Result<Request, ClientError> {
+    self.program.account(request_address(id))
+  }
+
+  /// The requests for `oracle` that are yet to be fulfilled.
+  pub fn pending(
+    &self,
+    oracle: OracleId,
+  ) -> Result<Vec<Request>, ClientError> {
+    let filters = vec![
+      RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+        ORACLE_OFFSET,
+        oracle.0.as_ref(),
+      )),
+      RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+        FULFILLED_OFFSET,
+        &[NONE_TAG],
+      )),
+    ];
+    let mut requests: Vec<Request> = self
+      .program
+      .accounts(filters)?
+      .into_iter()
+      .map(|(_, request)| request)
+      .collect();
+    requests.sort_by_key(|r| r.id);
+    Ok(requests)
+  }
+
+  /// Fulfills a request by writing `value` to the requested oracle, the payer
+  /// must be the owner of the oracle.
+  pub fn fulfill(
+    &self,
+    id: u64,
+    value: Option<i128>,
+    confidence: Option<u128>,
+  ) -> Result<Signature, ClientError> {
+    let request = self.get(id)?;
+    self
+      .program
+      .request()
+      .accounts(vvtec::accounts::FulfillRequest {
+        owner: self.program.payer(),
+        oracle: request.oracle,
+        request: request_address(id),
+        config: config_address(),
+      })
+      .args(vvtec::instruction::FulfillRequest { value, confidence })
+      .send()
+  }
+
+  /// Closes a request and returns its rent to the requester, who must be the
+  /// payer. Pending requests are withdrawn.
+  pub fn close(&self, id: u64) -> Result<Signature, ClientError> {
+    self
+      .program
+      .request()
+      .accounts(vvtec::accounts::CloseRequest {
+        requester: self.program.payer(),
+        request: request_address(id),
+      })
+      .args(vvtec::instruction::CloseRequest {})
+      .send()
+  }
+}
+
+/// The address of the request account with the given id.
+pub fn request_address(id: u64) -> Pubkey {
+  Pubkey::find_program_address(
+    &[Request::SEED, &id.to_le_bytes()],
+    &vvtec::id(),
+  )
+  .0
+}
@@ -0,0 +1,108 @@
+use anchor_client::solana_sdk::{
+  ed25519_program,
+  instruction::Instruction,
+  pubkey::Pubkey,
+  signature::{Keypair, Signature, Signer},
+};
+use vvtec::message::PriceMessage;
+
+/// Offset of the public key in the data of the ed25519 instruction, right
+/// after the header of a single signature.
+const PUBLIC_KEY_OFFSET: u16 = 2 + 7 * 2;
+
+const SIGNATURE_OFFSET: u16 = PUBLIC_KEY_OFFSET + 32;
+
+const MESSAGE_OFFSET: u16 = SIGNATURE_OFFSET + 64;
+
+/// The ed25519 program reads data marked with this index from its own
+/// instruction.
+const THIS_INSTRUCTION: u16 = u16::MAX;
+
+/// A feed value signed off-chain by the owner of the feed.
+///
+/// The owner signs values with [`SignedValue::sign`] and hands them to any
+/// relayer, who submits them with
+/// [`Oracle::submit_signed`](crate::Oracle::submit_signed) and pays for the
+/// transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedValue {
+  pub message: PriceMessage,
+  pub signer: Pubkey,
+  pub signature: Signature,
+}
+
+impl SignedValue {
+  pub fn sign(owner: &Keypair, message: PriceMessage) -> Self {
+    let signature = owner.sign_message(&message.to_bytes());
+    Self {
+      message,
+      signer: owner.pubkey(),
+      signature,
+    }
+  }
+
+  /// Returns true if the signature is valid for the message and signer.
+  pub fn verify(&self) -> bool {
+    self
+      .signature
+      .verify(self.signer.as_ref(), &self.message.to_bytes())
+  }
+
+  /// The ed25519 program instruction that verifies the signature, it must
+  /// immediately precede the `submit_signed` instruction.
+  pub fn verify_instruction(&self) -> Instruction {
+    let message = self.message.to_bytes();
+    let offsets = [
+      SIGNATURE_OFFSET,
+      THIS_INSTRUCTION,
+      PUBLIC_KEY_OFFSET,
+      THIS_INSTRUCTION,
+      MESSAGE_OFFSET,
+      message.len() as u16,
+      THIS_INSTRUCTION,
+    ];
+
+    let mut data = vec![1, 0];
+    for offset in offsets {
+      data.extend_from_slice(&offset.to_le_bytes());
+    }
+    data.extend_from_slice(self.signer.as_ref());
+    data.extend_from_slice(self.signature.as_ref());
+    data.extend_from_slice(&message);
+
+    Instruction {
+      program_id: ed25519_program::id(),
+      accounts: vec![],
+      data,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn lays_out_verify_instruction() {
+    let owner = Keypair::new();
+    let signed = SignedValue::sign(
+      &owner,
+      PriceMessage {
+        name: [1; 32],
+        parent: Pubkey::default(),
+        value: Some(-5),
+        confidence: None,
+        timestamp: 100,
+      },
+    );
+    assert!(signed.verify());
+
+    let data = signed.verify_instruction().data;
+    let at = |offset: u16, len: usize| {
+      &data[offset as usize..offset as usize + len]
+    };
+    assert_eq!(at(PUBLIC_KEY_OFFSET, 32), owner.pubkey().as_ref());
+    assert_eq!(at(SIGNATURE_OFFSET, 64), signed.signature.as_ref());
+    assert_eq!(&data[MESSAGE_OFFSET as usize..], signed.message.to_bytes());
+  }
+}
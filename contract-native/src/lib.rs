@@ -1,2 +1,15 @@
+//! The vvtec oracle program without anchor.
+//!
+//! Covers the lifecycle of root feeds in the global namespace: creating,
+//! updating and deleting feeds and transferring their ownership. Instructions
+//! are [`vvtec::native::OracleInstruction`]s and oracle accounts use the same
+//! layout as the anchor program, so both builds can serve the same clients.
+//! Feeds updated every slot can use the zero-copy
+//! [`vvtec::state::FastOracle`] layout instead, which is written in place.
+//! The protocol config (fees, pausing and the curation of the global
+//! namespace) is only enforced by the anchor program.
+
 mod processor;
 mod processors;
+
+pub use processor::process_instruction;
@@ -1,15 +1,21 @@
 use crate::processors::{
-  create::process_create, delete::process_delete, update::process_update,
+  create::process_create,
+  delete::process_delete,
+  fast::{process_create_fast, process_delete_fast, process_update_fast},
+  transfer::{process_accept_owner, process_propose_owner},
+  update::process_update,
 };
 use borsh::BorshDeserialize;
-use vvtec::OracleInstruction;
+use vvtec::native::OracleInstruction;
 use solana_program::{
-  account_info::AccountInfo, entrypoint, entrypoint::ProgramResult, msg,
+  account_info::AccountInfo, entrypoint::ProgramResult, msg,
   program_error::ProgramError, pubkey::Pubkey,
 };
 
-entrypoint!(process_instruction);
-fn process_instruction(
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
   program_id: &Pubkey,
   accounts: &[AccountInfo],
   instruction_data: &[u8],
@@ -20,14 +26,32 @@ fn process_instruction(
     .map_err(|_| ProgramError::InvalidInstructionData)?;
 
   match instruction {
-    OracleInstruction::Create { feed, balance } => {
-      process_create(program_id, accounts, balance, feed)
+    OracleInstruction::Create { feed } => {
+      process_create(program_id, accounts, feed)
     }
 
-    OracleInstruction::Update(value) => {
-      process_update(program_id, accounts, value)
+    OracleInstruction::Update { value, confidence } => {
+      process_update(program_id, accounts, value, confidence)
     }
 
     OracleInstruction::Delete => process_delete(program_id, accounts),
+
+    OracleInstruction::ProposeOwner(new_owner) => {
+      process_propose_owner(program_id, accounts, new_owner)
+    }
+
+    OracleInstruction::AcceptOwner => {
+      process_accept_owner(program_id, accounts)
+    }
+
+    OracleInstruction::CreateFast { name, exponent } => {
+      process_create_fast(program_id, accounts, name, exponent)
+    }
+
+    OracleInstruction::UpdateFast { value, confidence } => {
+      process_update_fast(program_id, accounts, value, confidence)
+    }
+
+    OracleInstruction::DeleteFast => process_delete_fast(program_id, accounts),
   }
 }
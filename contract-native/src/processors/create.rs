@@ -1,5 +1,7 @@
-use borsh::BorshSerialize;
-use vvtec::{Error, Feed};
+use vvtec::{
+  state::{name_seed, Oracle},
+  Feed,
+};
 use solana_program::{
   account_info::{next_account_info, AccountInfo},
   clock::Clock,
@@ -8,59 +10,81 @@ use solana_program::{
   program::invoke_signed,
   program_error::ProgramError,
   pubkey::Pubkey,
+  rent::Rent,
   system_instruction,
   sysvar::Sysvar,
 };
-use std::mem::size_of;
+
+use super::{anchor_error::*, store_oracle};
 
 pub(crate) fn process_create(
   program_id: &Pubkey,
   accounts: &[AccountInfo],
-  initial_balance: u64,
   feed: Feed,
 ) -> ProgramResult {
   msg!("processing feed create");
 
   let accounts_iter = &mut accounts.iter();
   let payer = next_account_info(accounts_iter)?;
-  let owner = next_account_info(accounts_iter)?;
   let oracle = next_account_info(accounts_iter)?;
+  let system_program = next_account_info(accounts_iter)?;
 
-  if owner.key == &Pubkey::default() {
+  if feed.owner == Pubkey::default() {
     msg!("The owner cannot be zero");
     return Err(ProgramError::InvalidArgument);
   }
 
-  if feed.name.len() > 32 {
-    msg!("Oracle name must be less than 32 bytes");
+  if feed.name.is_empty() || feed.name.len() > Oracle::MAX_NAME_LEN {
+    msg!("Oracle name must be between 1 and {} bytes", Oracle::MAX_NAME_LEN);
+    return Err(ProgramError::InvalidArgument);
+  }
+
+  if feed.name.contains('.') {
+    msg!("Oracle names cannot contain dots, they separate path segments");
+    return Err(ProgramError::InvalidArgument);
+  }
+
+  if feed.history_capacity > Oracle::MAX_HISTORY_CAPACITY {
+    msg!(
+      "Oracle history capacity must not exceed {}",
+      Oracle::MAX_HISTORY_CAPACITY
+    );
     return Err(ProgramError::InvalidArgument);
   }
 
+  let seed = name_seed(&feed.name);
   let (expected_oracle_acc, seed_bump) =
-    Pubkey::find_program_address(&[&feed.name], program_id);
+    Pubkey::find_program_address(&[&seed], program_id);
 
   if !payer.is_signer {
-    return Err(Error::MissingSignature.into());
+    return Err(ProgramError::Custom(ACCOUNT_NOT_SIGNER));
   }
 
   if &expected_oracle_acc != oracle.key {
-    return Err(Error::InvalidOracle.into());
+    return Err(ProgramError::Custom(CONSTRAINT_SEEDS));
   }
 
   // allocate oracle account
+  let space = Oracle::space(feed.name.len(), feed.history_capacity);
   invoke_signed(
     &system_instruction::create_account(
       payer.key,
       oracle.key,
-      initial_balance,
-      size_of::<Feed>() as u64,
+      Rent::get()?.minimum_balance(space),
+      space as u64,
       program_id,
     ),
-    &[payer.clone(), oracle.clone()],
-    &[&[&feed.name, &[seed_bump]]],
+    &[payer.clone(), oracle.clone(), system_program.clone()],
+    &[&[&seed, &[seed_bump]]],
   )?;
 
-  let mut feed = feed;
-  feed.updated_at = Clock::get()?.unix_timestamp;
-  Ok(feed.serialize(&mut oracle.data.borrow_mut().as_mut())?)
+  let mut state = Oracle::new(
+    feed.owner,
+    feed.name,
+    feed.exponent,
+    feed.policy,
+    feed.history_capacity,
+  );
+  state.record(feed.value, None, &Clock::get()?);
+  store_oracle(&state, oracle)
 }
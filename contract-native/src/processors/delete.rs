@@ -1,11 +1,56 @@
+use vvtec::{state::FeedMode, Error};
 use solana_program::{
-  account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey,
+  account_info::{next_account_info, AccountInfo},
+  entrypoint::ProgramResult,
+  msg,
+  program_error::ProgramError,
+  pubkey::Pubkey,
 };
 
+use super::{anchor_error::*, load_oracle, program_error};
+
 pub(crate) fn process_delete(
-  _program_id: &Pubkey,
-  _accounts: &[AccountInfo],
+  program_id: &Pubkey,
+  accounts: &[AccountInfo],
 ) -> ProgramResult {
   msg!("processing feed delete");
-  todo!()
+
+  let accounts_iter = &mut accounts.iter();
+  let owner = next_account_info(accounts_iter)?;
+  let oracle = next_account_info(accounts_iter)?;
+  let recipient = next_account_info(accounts_iter)?;
+
+  if !owner.is_signer {
+    msg!("missing oracle owner signature");
+    return Err(ProgramError::Custom(ACCOUNT_NOT_SIGNER));
+  }
+
+  let feed = load_oracle(oracle, program_id)?;
+
+  if feed.owner != *owner.key {
+    return Err(ProgramError::Custom(CONSTRAINT_HAS_ONE));
+  }
+
+  if !feed.is_root() {
+    return Err(program_error(Error::ParentMismatch));
+  }
+
+  if feed.children != 0 {
+    return Err(program_error(Error::OracleHasChildren));
+  }
+
+  if feed.mode != FeedMode::Direct {
+    return Err(program_error(Error::AggregatedOracle));
+  }
+
+  // refund the rent to the recipient, the runtime purges the emptied account
+  let refund = oracle.lamports();
+  **recipient.lamports.borrow_mut() = recipient
+    .lamports()
+    .checked_add(refund)
+    .ok_or_else(|| program_error(Error::Overflow))?;
+  **oracle.lamports.borrow_mut() = 0;
+  oracle.data.borrow_mut().fill(0);
+
+  Ok(())
 }
@@ -0,0 +1,159 @@
+use std::cell::RefMut;
+
+use vvtec::{
+  state::{name_seed, FastOracle, Oracle},
+  Error, FeedValue,
+};
+use solana_program::{
+  account_info::{next_account_info, AccountInfo},
+  clock::Clock,
+  entrypoint::ProgramResult,
+  msg,
+  program::invoke_signed,
+  program_error::ProgramError,
+  pubkey::Pubkey,
+  rent::Rent,
+  system_instruction,
+  sysvar::Sysvar,
+};
+
+use super::{anchor_error::*, program_error};
+
+/// Borrows the fast oracle stored in an account owned by this program in
+/// place, without decoding it.
+fn load_fast_oracle<'a>(
+  account_info: &'a AccountInfo,
+  owner_program_id: &Pubkey,
+) -> Result<RefMut<'a, FastOracle>, ProgramError> {
+  if account_info.owner != owner_program_id {
+    return Err(ProgramError::Custom(ACCOUNT_OWNED_BY_WRONG_PROGRAM));
+  }
+
+  let data = account_info.data.borrow_mut();
+  if data.len() < 8 {
+    return Err(ProgramError::Custom(ACCOUNT_DISCRIMINATOR_NOT_FOUND));
+  }
+  if data[..8] != FastOracle::DISCRIMINATOR {
+    return Err(ProgramError::Custom(ACCOUNT_DISCRIMINATOR_MISMATCH));
+  }
+  if data.len() != FastOracle::SPACE {
+    return Err(ProgramError::Custom(ACCOUNT_DID_NOT_DESERIALIZE));
+  }
+  Ok(RefMut::map(data, |data| {
+    bytemuck::from_bytes_mut(&mut data[8..])
+  }))
+}
+
+pub(crate) fn process_create_fast(
+  program_id: &Pubkey,
+  accounts: &[AccountInfo],
+  name: String,
+  exponent: i8,
+) -> ProgramResult {
+  msg!("processing fast feed create");
+
+  let accounts_iter = &mut accounts.iter();
+  let payer = next_account_info(accounts_iter)?;
+  let owner = next_account_info(accounts_iter)?;
+  let oracle = next_account_info(accounts_iter)?;
+  let system_program = next_account_info(accounts_iter)?;
+
+  if !payer.is_signer || !owner.is_signer {
+    return Err(ProgramError::Custom(ACCOUNT_NOT_SIGNER));
+  }
+
+  let seed = name_seed(&name);
+  let (expected_oracle_acc, seed_bump) = Pubkey::find_program_address(
+    &[FastOracle::SEED, owner.key.as_ref(), &seed],
+    program_id,
+  );
+
+  if &expected_oracle_acc != oracle.key {
+    return Err(ProgramError::Custom(CONSTRAINT_SEEDS));
+  }
+
+  if name.is_empty() || name.len() > Oracle::MAX_NAME_LEN {
+    msg!("Oracle name must be between 1 and {} bytes", Oracle::MAX_NAME_LEN);
+    return Err(ProgramError::InvalidArgument);
+  }
+
+  if name.contains('.') {
+    msg!("Oracle names cannot contain dots, they separate path segments");
+    return Err(ProgramError::InvalidArgument);
+  }
+
+  invoke_signed(
+    &system_instruction::create_account(
+      payer.key,
+      oracle.key,
+      Rent::get()?.minimum_balance(FastOracle::SPACE),
+      FastOracle::SPACE as u64,
+      program_id,
+    ),
+    &[payer.clone(), oracle.clone(), system_program.clone()],
+    &[&[FastOracle::SEED, owner.key.as_ref(), &seed, &[seed_bump]]],
+  )?;
+
+  oracle.data.borrow_mut()[..8].copy_from_slice(&FastOracle::DISCRIMINATOR);
+  let mut state = load_fast_oracle(oracle, program_id)?;
+  state.owner = *owner.key;
+  state.name_seed = seed;
+  state.exponent = exponent;
+
+  Ok(())
+}
+
+pub(crate) fn process_update_fast(
+  program_id: &Pubkey,
+  accounts: &[AccountInfo],
+  value: Option<FeedValue>,
+  confidence: Option<u128>,
+) -> ProgramResult {
+  let accounts_iter = &mut accounts.iter();
+  let owner = next_account_info(accounts_iter)?;
+  let oracle = next_account_info(accounts_iter)?;
+
+  if !owner.is_signer {
+    return Err(ProgramError::Custom(ACCOUNT_NOT_SIGNER));
+  }
+
+  let mut feed = load_fast_oracle(oracle, program_id)?;
+
+  if feed.owner != *owner.key {
+    return Err(ProgramError::Custom(CONSTRAINT_HAS_ONE));
+  }
+
+  feed.record(value, confidence, &Clock::get()?);
+
+  Ok(())
+}
+
+pub(crate) fn process_delete_fast(
+  program_id: &Pubkey,
+  accounts: &[AccountInfo],
+) -> ProgramResult {
+  msg!("processing fast feed delete");
+
+  let accounts_iter = &mut accounts.iter();
+  let owner = next_account_info(accounts_iter)?;
+  let oracle = next_account_info(accounts_iter)?;
+
+  if !owner.is_signer {
+    return Err(ProgramError::Custom(ACCOUNT_NOT_SIGNER));
+  }
+
+  if load_fast_oracle(oracle, program_id)?.owner != *owner.key {
+    return Err(ProgramError::Custom(CONSTRAINT_HAS_ONE));
+  }
+
+  // refund the rent to the owner, the runtime purges the emptied account
+  let refund = oracle.lamports();
+  **owner.lamports.borrow_mut() = owner
+    .lamports()
+    .checked_add(refund)
+    .ok_or_else(|| program_error(Error::Overflow))?;
+  **oracle.lamports.borrow_mut() = 0;
+  oracle.data.borrow_mut().fill(0);
+
+  Ok(())
+}
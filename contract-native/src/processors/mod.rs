@@ -1,3 +1,75 @@
+use vvtec::{state::Oracle, Error};
+
+use self::anchor_error::*;
+use solana_program::{
+  account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey,
+};
+
 pub mod create;
 pub mod delete;
+pub mod fast;
+pub mod transfer;
 pub mod update;
+
+/// Anchor offsets the codes of program errors by this much, so do we to fail
+/// with the same codes as the anchor program.
+const ERROR_CODE_OFFSET: u32 = 6000;
+
+/// Codes of the anchor framework errors the anchor program fails with when
+/// its account constraints are violated. The native program fails with the
+/// same codes when the equivalent checks fail.
+pub(crate) mod anchor_error {
+  pub const CONSTRAINT_HAS_ONE: u32 = 2001;
+  pub const CONSTRAINT_SEEDS: u32 = 2006;
+  pub const ACCOUNT_DISCRIMINATOR_NOT_FOUND: u32 = 3001;
+  pub const ACCOUNT_DISCRIMINATOR_MISMATCH: u32 = 3002;
+  pub const ACCOUNT_DID_NOT_DESERIALIZE: u32 = 3003;
+  pub const ACCOUNT_OWNED_BY_WRONG_PROGRAM: u32 = 3007;
+  pub const ACCOUNT_NOT_SIGNER: u32 = 3010;
+  pub const ACCOUNT_NOT_INITIALIZED: u32 = 3012;
+}
+
+pub(crate) fn program_error(error: Error) -> ProgramError {
+  ProgramError::Custom(ERROR_CODE_OFFSET + error as u32)
+}
+
+/// Decodes the oracle stored in an account owned by this program.
+///
+/// Accounts of older layouts have to be migrated by the anchor program first,
+/// they are too small for the current layout.
+pub(crate) fn load_oracle(
+  account_info: &AccountInfo,
+  owner_program_id: &Pubkey,
+) -> Result<Oracle, ProgramError> {
+  if account_info.data_is_empty() {
+    return Err(ProgramError::Custom(ACCOUNT_NOT_INITIALIZED));
+  }
+  if account_info.owner != owner_program_id {
+    return Err(ProgramError::Custom(ACCOUNT_OWNED_BY_WRONG_PROGRAM));
+  }
+
+  let data = account_info.data.borrow();
+  match Oracle::layout_version(&data) {
+    Some(Oracle::VERSION) => Oracle::from_account_data(&data)
+      .map_err(|_| ProgramError::Custom(ACCOUNT_DID_NOT_DESERIALIZE)),
+    Some(_) => {
+      msg!("Oracle uses an outdated layout, migrate it first");
+      Err(ProgramError::Custom(ACCOUNT_DID_NOT_DESERIALIZE))
+    }
+    None => Err(ProgramError::Custom(ACCOUNT_DISCRIMINATOR_MISMATCH)),
+  }
+}
+
+/// Writes `oracle` back to its account, in the layout of the anchor program.
+pub(crate) fn store_oracle(
+  oracle: &Oracle,
+  account_info: &AccountInfo,
+) -> Result<(), ProgramError> {
+  let bytes = oracle.to_account_data();
+  let mut data = account_info.data.borrow_mut();
+  if data.len() < bytes.len() {
+    return Err(ProgramError::AccountDataTooSmall);
+  }
+  data[..bytes.len()].copy_from_slice(&bytes);
+  Ok(())
+}
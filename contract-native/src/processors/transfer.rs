@@ -0,0 +1,62 @@
+use vvtec::Error;
+use solana_program::{
+  account_info::{next_account_info, AccountInfo},
+  entrypoint::ProgramResult,
+  msg,
+  program_error::ProgramError,
+  pubkey::Pubkey,
+};
+
+use super::{anchor_error::*, load_oracle, program_error, store_oracle};
+
+pub(crate) fn process_propose_owner(
+  program_id: &Pubkey,
+  accounts: &[AccountInfo],
+  new_owner: Option<Pubkey>,
+) -> ProgramResult {
+  msg!("processing feed owner proposal");
+
+  let accounts_iter = &mut accounts.iter();
+  let owner = next_account_info(accounts_iter)?;
+  let oracle = next_account_info(accounts_iter)?;
+
+  if !owner.is_signer {
+    msg!("missing oracle owner signature");
+    return Err(ProgramError::Custom(ACCOUNT_NOT_SIGNER));
+  }
+
+  let mut feed = load_oracle(oracle, program_id)?;
+
+  if feed.owner != *owner.key {
+    return Err(ProgramError::Custom(CONSTRAINT_HAS_ONE));
+  }
+
+  feed.pending_owner = new_owner;
+  store_oracle(&feed, oracle)
+}
+
+pub(crate) fn process_accept_owner(
+  program_id: &Pubkey,
+  accounts: &[AccountInfo],
+) -> ProgramResult {
+  msg!("processing feed owner acceptance");
+
+  let accounts_iter = &mut accounts.iter();
+  let new_owner = next_account_info(accounts_iter)?;
+  let oracle = next_account_info(accounts_iter)?;
+
+  if !new_owner.is_signer {
+    msg!("missing new oracle owner signature");
+    return Err(ProgramError::Custom(ACCOUNT_NOT_SIGNER));
+  }
+
+  let mut feed = load_oracle(oracle, program_id)?;
+
+  if feed.pending_owner != Some(*new_owner.key) {
+    return Err(program_error(Error::OwnerMismatch));
+  }
+
+  feed.owner = *new_owner.key;
+  feed.pending_owner = None;
+  store_oracle(&feed, oracle)
+}
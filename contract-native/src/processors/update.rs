@@ -1,43 +1,21 @@
-use std::mem::size_of;
-
-use borsh::{BorshDeserialize, BorshSerialize};
-use vvtec::{Error, Feed, FeedValue};
+use vvtec::{state::FeedMode, Error, FeedValue};
 use solana_program::{
   account_info::{next_account_info, AccountInfo},
   clock::Clock,
   entrypoint::ProgramResult,
   msg,
   program_error::ProgramError,
-  program_pack::IsInitialized,
   pubkey::Pubkey,
   sysvar::Sysvar,
 };
 
-fn get_account_data<T: BorshDeserialize + IsInitialized>(
-  account_info: &AccountInfo,
-  owner_program_id: &Pubkey,
-) -> Result<T, ProgramError> {
-  if account_info.data_is_empty() {
-    return Err(ProgramError::UninitializedAccount);
-  }
-  if account_info.owner != owner_program_id {
-    return Err(Error::OwnerMismatch.into());
-  }
-
-  let account: T = solana_program::borsh::try_from_slice_unchecked(
-    &account_info.data.borrow()[..size_of::<Feed>()],
-  )?;
-  if !account.is_initialized() {
-    Err(ProgramError::UninitializedAccount)
-  } else {
-    Ok(account)
-  }
-}
+use super::{anchor_error::*, load_oracle, program_error, store_oracle};
 
 pub(crate) fn process_update(
   program_id: &Pubkey,
   accounts: &[AccountInfo],
   value: Option<FeedValue>,
+  confidence: Option<u128>,
 ) -> ProgramResult {
   let accounts_iter = &mut accounts.iter();
   let owner = next_account_info(accounts_iter)?;
@@ -50,16 +28,19 @@ pub(crate) fn process_update(
 
   if !owner.is_signer {
     msg!("missing oracle owner signature");
-    return Err(Error::MissingSignature.into());
+    return Err(ProgramError::Custom(ACCOUNT_NOT_SIGNER));
   }
 
-  let mut feed = get_account_data::<Feed>(oracle, program_id)?;
+  let mut feed = load_oracle(oracle, program_id)?;
 
   if feed.owner != *owner.key {
-    return Err(Error::OwnerMismatch.into());
+    return Err(ProgramError::Custom(CONSTRAINT_HAS_ONE));
+  }
+
+  if feed.mode != FeedMode::Direct {
+    return Err(program_error(Error::AggregatedOracle));
   }
 
-  feed.value = value;
-  feed.updated_at = Clock::get()?.unix_timestamp;
-  Ok(feed.serialize(&mut oracle.data.borrow_mut().as_mut())?)
+  feed.record(value, confidence, &Clock::get()?);
+  store_oracle(&feed, oracle)
 }
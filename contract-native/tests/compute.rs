@@ -0,0 +1,257 @@
+//! Measures the compute units an update costs with the borsh layout of
+//! `Oracle` and with the zero-copy layout of `FastOracle`, in both programs.
+//!
+//! Compute is only metered for BPF builds, so the benchmark loads both
+//! programs from `BPF_OUT_DIR` and is ignored by default, see the README for
+//! how to build and run it.
+
+use anchor_lang::{AccountSerialize, InstructionData, ToAccountMetas};
+use solana_program_test::{ProgramTest, ProgramTestContext};
+use solana_sdk::{
+  account::Account,
+  instruction::Instruction,
+  native_token::LAMPORTS_PER_SOL,
+  pubkey::Pubkey,
+  signature::Signer,
+  system_program,
+  transaction::Transaction,
+};
+use vvtec::{
+  native::instruction as native,
+  state::{name_seed, FastOracle},
+};
+use vvtec_onchain::state::Config;
+
+const NAME: &str = "equities-nasdaq-aapl-close-adjusted";
+
+struct Bench {
+  context: ProgramTestContext,
+  native_id: Pubkey,
+  config: Pubkey,
+  treasury: Pubkey,
+}
+
+impl Bench {
+  async fn start() -> Self {
+    let native_id = Pubkey::new_unique();
+    let treasury = Pubkey::new_unique();
+    let config =
+      Pubkey::find_program_address(&[Config::SEED], &vvtec_onchain::ID).0;
+
+    let mut program_test = ProgramTest::default();
+    program_test.add_program("vvtec_onchain", vvtec_onchain::ID, None);
+    program_test.add_program("vvtec_native", native_id, None);
+
+    let mut context = program_test.start_with_context().await;
+    let mut data = Vec::new();
+    Config {
+      admin: context.payer.pubkey(),
+      paused: false,
+      treasury,
+      create_fee: 0,
+      update_fee: 0,
+      request_fee: 0,
+      next_request_id: 0,
+      reap_after_secs: 0,
+      curated: false,
+    }
+    .try_serialize(&mut data)
+    .unwrap();
+    context.set_account(
+      &config,
+      &Account {
+        lamports: LAMPORTS_PER_SOL,
+        data,
+        owner: vvtec_onchain::ID,
+        executable: false,
+        rent_epoch: 0,
+      }
+      .into(),
+    );
+
+    Self {
+      context,
+      native_id,
+      config,
+      treasury,
+    }
+  }
+
+  fn owner(&self) -> Pubkey {
+    self.context.payer.pubkey()
+  }
+
+  fn transaction(&self, instruction: Instruction) -> Transaction {
+    Transaction::new_signed_with_payer(
+      &[instruction],
+      Some(&self.context.payer.pubkey()),
+      &[&self.context.payer],
+      self.context.last_blockhash,
+    )
+  }
+
+  async fn send(&mut self, instruction: Instruction) {
+    let transaction = self.transaction(instruction);
+    self
+      .context
+      .banks_client
+      .process_transaction(transaction)
+      .await
+      .unwrap();
+  }
+
+  /// The compute units `instruction` consumes, without committing it.
+  async fn units(&mut self, instruction: Instruction) -> u64 {
+    let transaction = self.transaction(instruction);
+    let simulation = self
+      .context
+      .banks_client
+      .simulate_transaction(transaction)
+      .await
+      .unwrap();
+    simulation.result.unwrap().unwrap();
+    simulation.simulation_details.unwrap().units_consumed
+  }
+
+  /// Creates `NAME` in both layouts in the anchor program and returns the
+  /// regular and the fast update of it.
+  async fn anchor_updates(&mut self) -> (Instruction, Instruction) {
+    let owner = self.owner();
+    let oracle =
+      Pubkey::find_program_address(&[&name_seed(NAME)], &vvtec_onchain::ID).0;
+    let fast_oracle = Pubkey::find_program_address(
+      &[FastOracle::SEED, owner.as_ref(), &name_seed(NAME)],
+      &vvtec_onchain::ID,
+    )
+    .0;
+
+    self
+      .send(Instruction {
+        program_id: vvtec_onchain::ID,
+        accounts: vvtec_onchain::accounts::Create {
+          payer: owner,
+          oracle,
+          config: self.config,
+          treasury: self.treasury,
+          system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vvtec_onchain::instruction::Create {
+          feed: vvtec_onchain::Feed {
+            owner,
+            name: NAME.to_owned(),
+            value: Some(1),
+            exponent: -2,
+            policy: Default::default(),
+            history_capacity: 0,
+          },
+        }
+        .data(),
+      })
+      .await;
+    self
+      .send(Instruction {
+        program_id: vvtec_onchain::ID,
+        accounts: vvtec_onchain::accounts::CreateFast {
+          payer: owner,
+          owner,
+          oracle: fast_oracle,
+          config: self.config,
+          treasury: self.treasury,
+          system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vvtec_onchain::instruction::CreateFast {
+          name: NAME.to_owned(),
+          exponent: -2,
+        }
+        .data(),
+      })
+      .await;
+
+    let regular = Instruction {
+      program_id: vvtec_onchain::ID,
+      accounts: vvtec_onchain::accounts::Update {
+        owner,
+        oracle,
+        config: self.config,
+        treasury: self.treasury,
+        system_program: system_program::ID,
+      }
+      .to_account_metas(None),
+      data: vvtec_onchain::instruction::Update {
+        value: Some(2),
+        confidence: Some(1),
+      }
+      .data(),
+    };
+    let fast = Instruction {
+      program_id: vvtec_onchain::ID,
+      accounts: vvtec_onchain::accounts::UpdateFast {
+        owner,
+        oracle: fast_oracle,
+        config: self.config,
+        treasury: self.treasury,
+        system_program: system_program::ID,
+      }
+      .to_account_metas(None),
+      data: vvtec_onchain::instruction::UpdateFast {
+        value: Some(2),
+        confidence: Some(1),
+      }
+      .data(),
+    };
+    (regular, fast)
+  }
+
+  /// Creates `NAME` in both layouts in the native program and returns the
+  /// regular and the fast update of it.
+  async fn native_updates(&mut self) -> (Instruction, Instruction) {
+    let owner = self.owner();
+    let program_id = self.native_id;
+    let oracle = native::oracle_address(&program_id, NAME);
+    let fast_oracle = native::fast_oracle_address(&program_id, &owner, NAME);
+
+    self
+      .send(native::create(
+        &program_id,
+        &owner,
+        vvtec::Feed {
+          owner,
+          name: NAME.to_owned(),
+          value: Some(1),
+          exponent: -2,
+          policy: Default::default(),
+          history_capacity: 0,
+        },
+      ))
+      .await;
+    self
+      .send(native::create_fast(&program_id, &owner, &owner, NAME, -2))
+      .await;
+
+    (
+      native::update(&program_id, &owner, &oracle, Some(2), Some(1)),
+      native::update_fast(&program_id, &owner, &fast_oracle, Some(2), Some(1)),
+    )
+  }
+}
+
+#[tokio::test]
+#[ignore = "needs BPF builds of both programs in BPF_OUT_DIR"]
+async fn fast_updates_use_less_compute() {
+  let mut bench = Bench::start().await;
+  let (anchor, anchor_fast) = bench.anchor_updates().await;
+  let (native, native_fast) = bench.native_updates().await;
+
+  println!("{:<8} {:>10} {:>10}", "program", "borsh", "zero-copy");
+  for (program, regular, fast) in [
+    ("anchor", anchor, anchor_fast),
+    ("native", native, native_fast),
+  ] {
+    let regular = bench.units(regular).await;
+    let fast = bench.units(fast).await;
+    println!("{:<8} {:>10} {:>10}", program, regular, fast);
+    assert!(fast < regular, "{} fast update is not cheaper", program);
+  }
+}
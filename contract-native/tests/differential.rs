@@ -0,0 +1,458 @@
+//! Runs the same randomized sequences of instructions against the anchor
+//! program and the native program, and checks after every step that both
+//! fail with the same errors, hold the same account data and move the same
+//! lamports.
+
+use anchor_lang::{AccountSerialize, InstructionData, ToAccountMetas};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use solana_program_test::{
+  processor, BanksClientError, ProgramTest, ProgramTestContext,
+};
+use solana_sdk::{
+  account::Account,
+  instruction::Instruction,
+  native_token::LAMPORTS_PER_SOL,
+  pubkey::Pubkey,
+  signature::{Keypair, Signer},
+  system_program,
+  transaction::{Transaction, TransactionError},
+};
+use vvtec::{native::instruction as native, state::name_seed};
+use vvtec_onchain::state::Config;
+
+const NAMES: [&str; 3] =
+  ["btc", "eth-usd", "equities-nasdaq-aapl-close-adjusted"];
+
+const OWNER: usize = 0;
+const STRANGER: usize = 1;
+
+#[derive(Debug, Clone, Copy)]
+enum Step {
+  Create {
+    name: usize,
+    value: Option<i128>,
+    history_capacity: u16,
+  },
+  Update {
+    name: usize,
+    signer: usize,
+    value: Option<i128>,
+    confidence: Option<u128>,
+  },
+  Delete {
+    name: usize,
+    signer: usize,
+    recipient: usize,
+  },
+  ProposeOwner {
+    name: usize,
+    signer: usize,
+    new_owner: Option<usize>,
+  },
+  AcceptOwner {
+    name: usize,
+    signer: usize,
+  },
+}
+
+impl Step {
+  fn random(rng: &mut StdRng) -> Self {
+    let name = rng.gen_range(0..NAMES.len());
+    let signer = if rng.gen_bool(0.8) { OWNER } else { STRANGER };
+    let value = rng.gen_bool(0.9).then(|| rng.gen_range(-1_000..1_000));
+    match rng.gen_range(0..10) {
+      0..=1 => Step::Create {
+        name,
+        value,
+        history_capacity: rng.gen_range(0..4),
+      },
+      2..=6 => Step::Update {
+        name,
+        signer,
+        value,
+        confidence: rng.gen_bool(0.5).then(|| rng.gen_range(0..100)),
+      },
+      7 => Step::Delete {
+        name,
+        signer,
+        recipient: rng.gen_range(0..2),
+      },
+      8 => Step::ProposeOwner {
+        name,
+        signer,
+        new_owner: rng.gen_bool(0.8).then(|| rng.gen_range(0..2)),
+      },
+      _ => Step::AcceptOwner { name, signer },
+    }
+  }
+}
+
+struct Harness {
+  context: ProgramTestContext,
+  native_id: Pubkey,
+  actors: [Keypair; 2],
+  config: Pubkey,
+  treasury: Pubkey,
+}
+
+impl Harness {
+  async fn start() -> Self {
+    let native_id = Pubkey::new_unique();
+    let actors = [Keypair::new(), Keypair::new()];
+    let treasury = Pubkey::new_unique();
+    let config = Pubkey::find_program_address(
+      &[Config::SEED],
+      &vvtec_onchain::ID,
+    )
+    .0;
+
+    let mut program_test = ProgramTest::new(
+      "vvtec_onchain",
+      vvtec_onchain::ID,
+      processor!(vvtec_onchain::entry),
+    );
+    program_test.add_program(
+      "vvtec_native",
+      native_id,
+      processor!(vvtec_native::process_instruction),
+    );
+    for actor in &actors {
+      program_test.add_account(
+        actor.pubkey(),
+        Account::new(100 * LAMPORTS_PER_SOL, 0, &system_program::ID),
+      );
+    }
+
+    let mut data = Vec::new();
+    Config {
+      admin: actors[OWNER].pubkey(),
+      paused: false,
+      treasury,
+      create_fee: 0,
+      update_fee: 0,
+      request_fee: 0,
+      next_request_id: 0,
+      reap_after_secs: 0,
+      curated: false,
+    }
+    .try_serialize(&mut data)
+    .unwrap();
+    program_test.add_account(
+      config,
+      Account {
+        lamports: LAMPORTS_PER_SOL,
+        data,
+        owner: vvtec_onchain::ID,
+        executable: false,
+        rent_epoch: 0,
+      },
+    );
+
+    Self {
+      context: program_test.start_with_context().await,
+      native_id,
+      actors,
+      config,
+      treasury,
+    }
+  }
+
+  fn anchor_oracle(&self, name: usize) -> Pubkey {
+    Pubkey::find_program_address(
+      &[&name_seed(NAMES[name])],
+      &vvtec_onchain::ID,
+    )
+    .0
+  }
+
+  fn native_oracle(&self, name: usize) -> Pubkey {
+    native::oracle_address(&self.native_id, NAMES[name])
+  }
+
+  /// The signer of `step` and the instructions it is made of for the anchor
+  /// and the native program.
+  fn instructions(&self, step: Step) -> (usize, Instruction, Instruction) {
+    let key = |actor: usize| self.actors[actor].pubkey();
+    match step {
+      Step::Create {
+        name,
+        value,
+        history_capacity,
+      } => {
+        let owner = key(OWNER);
+        let anchor = Instruction {
+          program_id: vvtec_onchain::ID,
+          accounts: vvtec_onchain::accounts::Create {
+            payer: owner,
+            oracle: self.anchor_oracle(name),
+            config: self.config,
+            treasury: self.treasury,
+            system_program: system_program::ID,
+          }
+          .to_account_metas(None),
+          data: vvtec_onchain::instruction::Create {
+            feed: vvtec_onchain::Feed {
+              owner,
+              name: NAMES[name].to_owned(),
+              value,
+              exponent: -2,
+              policy: Default::default(),
+              history_capacity,
+            },
+          }
+          .data(),
+        };
+        let native = native::create(
+          &self.native_id,
+          &owner,
+          vvtec::Feed {
+            owner,
+            name: NAMES[name].to_owned(),
+            value,
+            exponent: -2,
+            policy: Default::default(),
+            history_capacity,
+          },
+        );
+        (OWNER, anchor, native)
+      }
+      Step::Update {
+        name,
+        signer,
+        value,
+        confidence,
+      } => {
+        let anchor = Instruction {
+          program_id: vvtec_onchain::ID,
+          accounts: vvtec_onchain::accounts::Update {
+            owner: key(signer),
+            oracle: self.anchor_oracle(name),
+            config: self.config,
+            treasury: self.treasury,
+            system_program: system_program::ID,
+          }
+          .to_account_metas(None),
+          data: vvtec_onchain::instruction::Update { value, confidence }
+            .data(),
+        };
+        let native = native::update(
+          &self.native_id,
+          &key(signer),
+          &self.native_oracle(name),
+          value,
+          confidence,
+        );
+        (signer, anchor, native)
+      }
+      Step::Delete {
+        name,
+        signer,
+        recipient,
+      } => {
+        let anchor = Instruction {
+          program_id: vvtec_onchain::ID,
+          accounts: vvtec_onchain::accounts::Delete {
+            owner: key(signer),
+            oracle: self.anchor_oracle(name),
+            recipient: key(recipient),
+          }
+          .to_account_metas(None),
+          data: vvtec_onchain::instruction::Delete {}.data(),
+        };
+        let native = native::delete(
+          &self.native_id,
+          &key(signer),
+          &self.native_oracle(name),
+          &key(recipient),
+        );
+        (signer, anchor, native)
+      }
+      Step::ProposeOwner {
+        name,
+        signer,
+        new_owner,
+      } => {
+        let new_owner = new_owner.map(key);
+        let anchor = Instruction {
+          program_id: vvtec_onchain::ID,
+          accounts: vvtec_onchain::accounts::ProposeOwner {
+            owner: key(signer),
+            oracle: self.anchor_oracle(name),
+          }
+          .to_account_metas(None),
+          data: vvtec_onchain::instruction::ProposeOwner { new_owner }.data(),
+        };
+        let native = native::propose_owner(
+          &self.native_id,
+          &key(signer),
+          &self.native_oracle(name),
+          new_owner,
+        );
+        (signer, anchor, native)
+      }
+      Step::AcceptOwner { name, signer } => {
+        let anchor = Instruction {
+          program_id: vvtec_onchain::ID,
+          accounts: vvtec_onchain::accounts::AcceptOwner {
+            new_owner: key(signer),
+            oracle: self.anchor_oracle(name),
+          }
+          .to_account_metas(None),
+          data: vvtec_onchain::instruction::AcceptOwner {}.data(),
+        };
+        let native = native::accept_owner(
+          &self.native_id,
+          &key(signer),
+          &self.native_oracle(name),
+        );
+        (signer, anchor, native)
+      }
+    }
+  }
+
+  /// Sends `instruction` signed by `signer`, returns the error it failed
+  /// with, if any, and the lamports every actor gained.
+  ///
+  /// The transaction fee is paid by the context payer, so the gains only
+  /// reflect the lamports the program moved.
+  async fn send(
+    &mut self,
+    signer: usize,
+    instruction: Instruction,
+  ) -> (Result<(), TransactionError>, [i128; 2]) {
+    let before = self.balances().await;
+    let payer = &self.context.payer;
+    let blockhash = self.context.banks_client.get_latest_blockhash().await;
+    let transaction = Transaction::new_signed_with_payer(
+      &[instruction],
+      Some(&payer.pubkey()),
+      &[payer, &self.actors[signer]],
+      blockhash.unwrap(),
+    );
+    let result =
+      match self.context.banks_client.process_transaction(transaction).await {
+        Ok(()) => Ok(()),
+        Err(BanksClientError::TransactionError(e)) => Err(e),
+        Err(e) => panic!("transaction was not processed: {}", e),
+      };
+    let after = self.balances().await;
+    (result, [after[0] - before[0], after[1] - before[1]])
+  }
+
+  async fn balances(&mut self) -> [i128; 2] {
+    let [owner, stranger] = [OWNER, STRANGER].map(|a| self.actors[a].pubkey());
+    [
+      self.balance(&owner).await as i128,
+      self.balance(&stranger).await as i128,
+    ]
+  }
+
+  /// The slot transactions are currently processed in.
+  async fn slot(&mut self) -> u64 {
+    self.context.banks_client.get_root_slot().await.unwrap() + 1
+  }
+
+  async fn balance(&mut self, address: &Pubkey) -> u64 {
+    self.context.banks_client.get_balance(*address).await.unwrap()
+  }
+
+  async fn account(&mut self, address: Pubkey) -> Option<Account> {
+    self.context.banks_client.get_account(address).await.unwrap()
+  }
+
+  /// Applies `step` to both programs within the same slot and compares the
+  /// outcomes.
+  async fn check(&mut self, step: Step) {
+    let (signer, anchor, native) = self.instructions(step);
+    let (anchor_result, anchor_gains) = self.send(signer, anchor).await;
+    let (native_result, native_gains) = self.send(signer, native).await;
+    assert_eq!(anchor_result, native_result, "errors differ in {:?}", step);
+    assert_eq!(anchor_gains, native_gains, "lamports differ in {:?}", step);
+
+    for (index, name) in NAMES.iter().enumerate() {
+      let anchor = self.account(self.anchor_oracle(index)).await;
+      let native = self.account(self.native_oracle(index)).await;
+      match (anchor, native) {
+        (None, None) => {}
+        (Some(anchor), Some(native)) => {
+          assert_eq!(anchor.lamports, native.lamports, "rent of {}", name);
+          assert_eq!(anchor.data, native.data, "data of {}", name);
+        }
+        (anchor, native) => panic!(
+          "{} exists only in one program after {:?}: {:?} / {:?}",
+          name, step, anchor, native
+        ),
+      }
+    }
+  }
+}
+
+#[tokio::test]
+async fn programs_agree_on_random_sequences() {
+  for seed in 0..8 {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut harness = Harness::start().await;
+    let mut slot = harness.slot().await;
+    for _ in 0..48 {
+      // a later slot gives every step a fresh blockhash and clock
+      slot += rng.gen_range(1..4);
+      harness.context.warp_to_slot(slot).unwrap();
+      harness.check(Step::random(&mut rng)).await;
+    }
+  }
+}
+
+#[tokio::test]
+async fn programs_agree_on_ownership_transfer() {
+  let mut harness = Harness::start().await;
+  let steps = [
+    Step::Create {
+      name: 2,
+      value: Some(1),
+      history_capacity: 2,
+    },
+    Step::ProposeOwner {
+      name: 2,
+      signer: OWNER,
+      new_owner: Some(STRANGER),
+    },
+    Step::Update {
+      name: 2,
+      signer: STRANGER,
+      value: Some(2),
+      confidence: None,
+    },
+    Step::AcceptOwner {
+      name: 2,
+      signer: STRANGER,
+    },
+    Step::Update {
+      name: 2,
+      signer: STRANGER,
+      value: Some(3),
+      confidence: Some(1),
+    },
+    Step::Delete {
+      name: 2,
+      signer: OWNER,
+      recipient: OWNER,
+    },
+    Step::Delete {
+      name: 2,
+      signer: STRANGER,
+      recipient: OWNER,
+    },
+    Step::Update {
+      name: 2,
+      signer: STRANGER,
+      value: None,
+      confidence: None,
+    },
+  ];
+  let mut slot = harness.slot().await;
+  for step in steps {
+    slot += 1;
+    harness.context.warp_to_slot(slot).unwrap();
+    harness.check(step).await;
+  }
+}
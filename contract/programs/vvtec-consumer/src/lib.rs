@@ -0,0 +1,63 @@
+//! A sample program showing how to consume vvtec feeds from other programs.
+
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod vvtec_consumer {
+    use super::*;
+
+    /// Stores the value of a vvtec feed in the reading account, as long as it
+    /// was updated within `max_age_secs` and its confidence interval is within
+    /// `max_confidence_bps` of the value.
+    pub fn read_price(
+        ctx: Context<ReadPrice>,
+        max_age_secs: i64,
+        max_confidence_bps: u16,
+    ) -> Result<()> {
+        let oracle = vvtec::consumer::load_confident(
+            &ctx.accounts.oracle,
+            max_age_secs,
+            max_confidence_bps,
+        )?;
+
+        let reading = &mut ctx.accounts.reading;
+        reading.oracle = ctx.accounts.oracle.key();
+        reading.value = oracle.value.unwrap_or_default();
+        reading.exponent = oracle.exponent;
+        reading.updated_at = oracle.updated_at;
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ReadPrice<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+    /// CHECK: verified by `vvtec::consumer::load_confident`
+    oracle: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + Reading::LEN,
+        seeds = [b"reading", oracle.key().as_ref()],
+        bump
+    )]
+    reading: Account<'info, Reading>,
+    system_program: Program<'info, System>,
+}
+
+/// The last value read from a vvtec feed.
+#[account]
+pub struct Reading {
+    pub oracle: Pubkey,
+    pub value: i128,
+    pub exponent: i8,
+    pub updated_at: i64,
+}
+
+impl Reading {
+    pub const LEN: usize = 32 + 16 + 1 + 8;
+}
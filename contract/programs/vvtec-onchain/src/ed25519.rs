@@ -0,0 +1,68 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        ed25519_program,
+        sysvar::instructions::{
+            load_current_index_checked, load_instruction_at_checked,
+        },
+    },
+};
+
+use crate::error::Error;
+
+/// Size of the number of signatures, the padding byte and the offsets of a
+/// single signature in the data of an ed25519 program instruction.
+const HEADER_LEN: usize = 2 + 7 * 2;
+
+/// Marks data stored within the ed25519 instruction itself.
+const THIS_INSTRUCTION: u16 = u16::MAX;
+
+/// Checks that the instruction preceding the current one makes the ed25519
+/// program verify a signature of `message` by `signer`.
+///
+/// The ed25519 program fails the whole transaction if the signature is
+/// invalid, so it is enough to check what it was asked to verify.
+pub fn verify_previous(
+    instructions: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current = load_current_index_checked(instructions)?;
+    if current == 0 {
+        return Err(Error::MissingSignature.into());
+    }
+    let ix = load_instruction_at_checked(current as usize - 1, instructions)?;
+    if ix.program_id != ed25519_program::ID || !ix.accounts.is_empty() {
+        return Err(Error::MissingSignature.into());
+    }
+
+    let data = &ix.data;
+    if data.len() < HEADER_LEN || data[0] != 1 {
+        return Err(Error::InvalidSignature.into());
+    }
+    let offset =
+        |i: usize| u16::from_le_bytes([data[2 + 2 * i], data[3 + 2 * i]]);
+    let (signature_ix, public_key_offset, public_key_ix) =
+        (offset(1), offset(2), offset(3));
+    let (message_offset, message_size, message_ix) =
+        (offset(4), offset(5), offset(6));
+    if signature_ix != THIS_INSTRUCTION
+        || public_key_ix != THIS_INSTRUCTION
+        || message_ix != THIS_INSTRUCTION
+    {
+        return Err(Error::InvalidSignature.into());
+    }
+
+    let slice = |start: u16, len: usize| {
+        data.get(start as usize..start as usize + len)
+            .ok_or(Error::InvalidSignature)
+    };
+    if slice(public_key_offset, 32)? != signer.as_ref() {
+        return Err(Error::OwnerMismatch.into());
+    }
+    if slice(message_offset, message_size as usize)? != message {
+        return Err(Error::InvalidSignature.into());
+    }
+
+    Ok(())
+}
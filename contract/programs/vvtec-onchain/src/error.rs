@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+/// Errors that may be returned by the program.
+#[error_code]
+pub enum Error {
+  #[msg("Lamport balance below rent-exempt threshold")]
+  NotRentExempt,
+
+  #[msg("Insufficient funds")]
+  InsufficientFunds,
+
+  #[msg("Owner does not match")]
+  OwnerMismatch,
+
+  #[msg("Missing signature")]
+  MissingSignature,
+
+  #[msg("Signature format is invalid")]
+  InvalidSignature,
+
+  #[msg("Invalid oracle")]
+  InvalidOracle,
+
+  #[msg("Oracle is unititialized")]
+  UninitializedOracle,
+
+  #[msg("Invalid instruction")]
+  InvalidInstruction,
+
+  #[msg("State is invalid for requested operation")]
+  InvalidState,
+
+  #[msg("Value format is not support")]
+  InvalidValue,
+
+  #[msg("Cannot connect to validtor cluster")]
+  InvalidNetwork,
+
+  #[msg("Error in the underlying RPC")]
+  RpcError,
+
+  #[msg("Internal system error")]
+  IoError,
+
+  #[msg("Solana program error")]
+  ProgramError,
+
+  #[msg("Oracle still has child feeds attached")]
+  OracleHasChildren,
+
+  #[msg("Oracle is not a child of the given parent")]
+  ParentMismatch,
+
+  #[msg("Oracle value is aggregated or derived from other sources")]
+  AggregatedOracle,
+
+  #[msg("Signer is not an authorized publisher of this oracle")]
+  UnauthorizedPublisher,
+
+  #[msg("Quorum must be between one and the number of publishers")]
+  InvalidQuorum,
+
+  #[msg("Oracle has no value")]
+  EmptyOracle,
+
+  #[msg("Oracle value is older than the allowed age")]
+  StaleOracle,
+
+  #[msg("Oracle confidence interval is wider than allowed")]
+  ConfidenceTooWide,
+
+  #[msg("Program is paused")]
+  Paused,
+
+  #[msg("Request was already fulfilled")]
+  RequestFulfilled,
+
+  #[msg("Signed value is not newer than the current value")]
+  OutdatedMessage,
+
+  #[msg("Derivation sources do not match its expression")]
+  InvalidDerivation,
+
+  #[msg("Metadata field exceeds its size limit")]
+  InvalidMetadata,
+
+  #[msg("Only the admin can create feeds in the global namespace")]
+  CuratedNamespace,
+
+  #[msg("Oracle was written too recently to be reaped")]
+  OracleNotAbandoned,
+
+  #[msg("Arithmetic overflow")]
+  Overflow,
+}
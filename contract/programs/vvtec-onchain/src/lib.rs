@@ -1,8 +1,22 @@
+mod ed25519;
+pub mod error;
+pub mod events;
+pub mod message;
 pub mod state;
 
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::sysvar, system_program};
 
-use state::Oracle;
+use error::Error;
+use message::PriceMessage;
+use events::{
+    OracleCreated, OracleDeleted, OracleUpdated, RequestFulfilled,
+    RequestOpened,
+};
+use state::{
+    name_seed, Aggregator, Config, Derivation, Expression, FastOracle,
+    FeedMetadata, FeedMode, Metadata, Oracle, Request, Submission,
+    UpdatePolicy,
+};
 
 declare_id!("vvtecC41zqsHouFA6EqSdcyJL9MdL9sk8E3pZWNQzyAY");
 
@@ -10,79 +24,1427 @@ declare_id!("vvtecC41zqsHouFA6EqSdcyJL9MdL9sk8E3pZWNQzyAY");
 pub mod vvtec_onchain {
     use super::*;
 
-    pub fn create(ctx: Context<Create>, feed: Feed) -> Result<()> {
-        if feed.name.len() > 32 {
-            msg!("Oracle name must be less than 32 bytes");
+    pub fn init_config(
+        ctx: Context<InitConfig>,
+        treasury: Pubkey,
+        create_fee: u64,
+        update_fee: u64,
+        request_fee: u64,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.authority.key();
+        config.paused = false;
+        config.treasury = treasury;
+        config.create_fee = create_fee;
+        config.update_fee = update_fee;
+        config.request_fee = request_fee;
+
+        Ok(())
+    }
+
+    pub fn set_admin(ctx: Context<Configure>, admin: Pubkey) -> Result<()> {
+        ctx.accounts.config.admin = admin;
+
+        Ok(())
+    }
+
+    pub fn set_paused(ctx: Context<Configure>, paused: bool) -> Result<()> {
+        ctx.accounts.config.paused = paused;
+
+        Ok(())
+    }
+
+    pub fn set_fees(
+        ctx: Context<Configure>,
+        treasury: Pubkey,
+        create_fee: u64,
+        update_fee: u64,
+        request_fee: u64,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.treasury = treasury;
+        config.create_fee = create_fee;
+        config.update_fee = update_fee;
+        config.request_fee = request_fee;
+
+        Ok(())
+    }
+
+    pub fn set_reap_after(
+        ctx: Context<Configure>,
+        reap_after_secs: i64,
+    ) -> Result<()> {
+        if reap_after_secs < 0 {
+            msg!("The reap period cannot be negative");
             return Err(ProgramError::InvalidArgument.into());
         }
+        ctx.accounts.config.reap_after_secs = reap_after_secs;
+
+        Ok(())
+    }
+
+    pub fn set_curated(ctx: Context<Configure>, curated: bool) -> Result<()> {
+        ctx.accounts.config.curated = curated;
+
+        Ok(())
+    }
+
+    pub fn create(ctx: Context<Create>, feed: Feed) -> Result<()> {
+        feed.validate()?;
+        charge_fee(
+            &ctx.accounts.payer,
+            &ctx.accounts.treasury,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.create_fee,
+        )?;
+
+        init_root(&mut ctx.accounts.oracle, feed, Pubkey::default())
+    }
+
+    pub fn create_namespaced(
+        ctx: Context<CreateNamespaced>,
+        feed: Feed,
+    ) -> Result<()> {
+        feed.validate()?;
+        charge_fee(
+            &ctx.accounts.payer,
+            &ctx.accounts.treasury,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.create_fee,
+        )?;
+
+        let namespace = feed.owner;
+        init_root(&mut ctx.accounts.oracle, feed, namespace)
+    }
+
+    pub fn attach_child(ctx: Context<AttachChild>, feed: Feed) -> Result<()> {
+        feed.validate()?;
+        charge_fee(
+            &ctx.accounts.payer,
+            &ctx.accounts.treasury,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.create_fee,
+        )?;
+
+        let parent = &mut ctx.accounts.parent;
+        parent.children =
+            parent.children.checked_add(1).ok_or(Error::Overflow)?;
+
+        let child = &mut ctx.accounts.child;
+        child.version = Oracle::VERSION;
+        child.owner = feed.owner;
+        child.name_seed = name_seed(&feed.name);
+        child.name = feed.name;
+        child.parent = parent.key();
+        child.namespace = parent.namespace;
+        child.exponent = feed.exponent;
+        child.policy = feed.policy;
+        child.history_capacity = feed.history_capacity;
+        child.record(feed.value, None, &Clock::get()?);
+
+        emit!(OracleCreated {
+            oracle: child.key(),
+            owner: child.owner,
+            name: child.name.clone(),
+            value: child.value,
+            timestamp: child.updated_at,
+        });
+
+        Ok(())
+    }
+
+    pub fn update(
+        ctx: Context<Update>,
+        value: Option<i128>,
+        confidence: Option<u128>,
+    ) -> Result<()> {
+        charge_fee(
+            &ctx.accounts.owner,
+            &ctx.accounts.treasury,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.update_fee,
+        )?;
+        record_value(
+            &mut ctx.accounts.oracle,
+            value,
+            confidence,
+            &Clock::get()?,
+        );
+
+        Ok(())
+    }
+
+    pub fn batch_update<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchUpdate<'info>>,
+        updates: Vec<FeedUpdate>,
+    ) -> Result<()> {
+        if updates.len() != ctx.remaining_accounts.len() {
+            msg!(
+                "Expected {} oracle accounts, got {}",
+                updates.len(),
+                ctx.remaining_accounts.len()
+            );
+            return Err(ProgramError::NotEnoughAccountKeys.into());
+        }
+
+        let fee = ctx
+            .accounts
+            .config
+            .update_fee
+            .checked_mul(updates.len() as u64)
+            .ok_or(Error::Overflow)?;
+        charge_fee(
+            &ctx.accounts.owner,
+            &ctx.accounts.treasury,
+            &ctx.accounts.system_program,
+            fee,
+        )?;
+
+        let clock = Clock::get()?;
+        for (info, update) in ctx.remaining_accounts.iter().zip(updates) {
+            if !info.is_writable {
+                return Err(ErrorCode::ConstraintMut.into());
+            }
+            let mut oracle = Account::<Oracle>::try_from(info)?;
+            let (address, _) = Pubkey::find_program_address(
+                &[oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+                ctx.program_id,
+            );
+            if address != info.key() {
+                return Err(ErrorCode::ConstraintSeeds.into());
+            }
+            if oracle.owner != ctx.accounts.owner.key() {
+                return Err(Error::OwnerMismatch.into());
+            }
+            if oracle.mode != FeedMode::Direct {
+                return Err(Error::AggregatedOracle.into());
+            }
+
+            record_value(&mut oracle, update.value, update.confidence, &clock);
+            oracle.exit(ctx.program_id)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn submit_signed(
+        ctx: Context<SubmitSigned>,
+        message: PriceMessage,
+    ) -> Result<()> {
+        let oracle = &ctx.accounts.oracle;
+        if message.name != oracle.name_seed
+            || message.parent != oracle.scope()
+        {
+            return Err(Error::InvalidOracle.into());
+        }
+        let clock = Clock::get()?;
+        if message.timestamp <= oracle.updated_at {
+            return Err(Error::OutdatedMessage.into());
+        }
+        if message.timestamp > clock.unix_timestamp {
+            msg!("Signed value is from the future");
+            return Err(Error::InvalidValue.into());
+        }
+        ed25519::verify_previous(
+            &ctx.accounts.instructions,
+            &oracle.owner,
+            &message.to_bytes(),
+        )?;
+
+        charge_fee(
+            &ctx.accounts.relayer,
+            &ctx.accounts.treasury,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.update_fee,
+        )?;
+        record_value(
+            &mut ctx.accounts.oracle,
+            message.value,
+            message.confidence,
+            // the value is as of the signed time
+            &Clock {
+                unix_timestamp: message.timestamp,
+                ..clock
+            },
+        );
+
+        Ok(())
+    }
+
+    pub fn open_request(ctx: Context<OpenRequest>) -> Result<()> {
+        charge_fee(
+            &ctx.accounts.requester,
+            &ctx.accounts.treasury,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.request_fee,
+        )?;
+
+        let config = &mut ctx.accounts.config;
+        let request = &mut ctx.accounts.request;
+        request.id = config.next_request_id;
+        request.oracle = ctx.accounts.oracle.key();
+        request.requester = ctx.accounts.requester.key();
+        request.requested_at = Clock::get()?.unix_timestamp;
+        config.next_request_id =
+            config.next_request_id.checked_add(1).ok_or(Error::Overflow)?;
+
+        emit!(RequestOpened {
+            request: request.key(),
+            id: request.id,
+            oracle: request.oracle,
+            requester: request.requester,
+            timestamp: request.requested_at,
+        });
+
+        Ok(())
+    }
+
+    pub fn fulfill_request(
+        ctx: Context<FulfillRequest>,
+        value: Option<i128>,
+        confidence: Option<u128>,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let oracle = &mut ctx.accounts.oracle;
+        record_value(oracle, value, confidence, &clock);
+
+        let request = &mut ctx.accounts.request;
+        request.fulfilled_at = Some(oracle.updated_at);
+        request.value = oracle.value;
+        request.confidence = oracle.confidence;
+        request.round_id = oracle.round_id;
+
+        emit!(RequestFulfilled {
+            request: request.key(),
+            id: request.id,
+            oracle: oracle.key(),
+            value: request.value,
+            round_id: request.round_id,
+        });
+
+        Ok(())
+    }
+
+    pub fn close_request(_ctx: Context<CloseRequest>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn check_health(ctx: Context<CheckHealth>) -> Result<bool> {
+        let now = Clock::get()?.unix_timestamp;
+        let healthy = ctx.accounts.oracle.is_healthy(now);
+        msg!("Oracle is {}", if healthy { "healthy" } else { "unhealthy" });
+
+        Ok(healthy)
+    }
+
+    pub fn detach_child(ctx: Context<DetachChild>) -> Result<()> {
+        let parent = &mut ctx.accounts.parent;
+        parent.children =
+            parent.children.checked_sub(1).ok_or(Error::Overflow)?;
+
+        emit!(OracleDeleted {
+            oracle: ctx.accounts.child.key(),
+            owner: ctx.accounts.child.owner,
+        });
+
+        Ok(())
+    }
+
+    pub fn propose_owner(
+        ctx: Context<ProposeOwner>,
+        new_owner: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.oracle.pending_owner = new_owner;
+
+        Ok(())
+    }
+
+    pub fn accept_owner(ctx: Context<AcceptOwner>) -> Result<()> {
+        let oracle = &mut ctx.accounts.oracle;
+        oracle.owner = ctx.accounts.new_owner.key();
+        oracle.pending_owner = None;
+
+        Ok(())
+    }
+
+    pub fn init_aggregator(
+        ctx: Context<InitAggregator>,
+        publishers: Vec<Pubkey>,
+        quorum: u8,
+        max_age_slots: u64,
+    ) -> Result<()> {
+        if quorum == 0 || quorum as usize > publishers.len() {
+            return Err(Error::InvalidQuorum.into());
+        }
+        for (i, publisher) in publishers.iter().enumerate() {
+            if publishers[..i].contains(publisher) {
+                msg!("Duplicate publisher {}", publisher);
+                return Err(ProgramError::InvalidArgument.into());
+            }
+        }
+
+        let oracle = &mut ctx.accounts.oracle;
+        oracle.mode = FeedMode::Aggregate;
+
+        let aggregator = &mut ctx.accounts.aggregator;
+        aggregator.oracle = oracle.key();
+        aggregator.quorum = quorum;
+        aggregator.max_age_slots = max_age_slots;
+        aggregator.submissions = publishers
+            .into_iter()
+            .map(|publisher| Submission {
+                publisher,
+                value: 0,
+                slot: 0,
+            })
+            .collect();
+
+        Ok(())
+    }
+
+    pub fn submit(ctx: Context<Submit>, value: i128) -> Result<()> {
+        let clock = Clock::get()?;
+        let aggregator = &mut ctx.accounts.aggregator;
+        let submission = aggregator
+            .submission_mut(ctx.accounts.publisher.key)
+            .ok_or(Error::UnauthorizedPublisher)?;
+        submission.value = value;
+        submission.slot = clock.slot;
+
+        if let Some((median, confidence)) = aggregator.aggregate(clock.slot) {
+            record_value(
+                &mut ctx.accounts.oracle,
+                Some(median),
+                Some(confidence),
+                &clock,
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn close_aggregator(ctx: Context<CloseAggregator>) -> Result<()> {
+        ctx.accounts.oracle.mode = FeedMode::Direct;
+
+        Ok(())
+    }
+
+    pub fn init_derivation(
+        ctx: Context<InitDerivation>,
+        expression: Expression,
+    ) -> Result<()> {
         let oracle = &mut ctx.accounts.oracle;
-        oracle.owner = feed.owner;
-        oracle.name = feed.name;
-        oracle.value = feed.value;
-        oracle.updated_at = Clock::get()?.unix_timestamp;
+        let sources = expression.sources();
+        if sources.is_empty()
+            || sources.len() > Derivation::MAX_SOURCES
+            || sources.contains(&oracle.key())
+        {
+            return Err(Error::InvalidDerivation.into());
+        }
+        oracle.mode = FeedMode::Derived;
+
+        let derivation = &mut ctx.accounts.derivation;
+        derivation.oracle = oracle.key();
+        derivation.expression = expression;
 
         Ok(())
     }
 
-    pub fn update(ctx: Context<Update>, value: Option<u128>) -> Result<()> {
+    pub fn refresh<'info>(
+        ctx: Context<'_, '_, '_, 'info, Refresh<'info>>,
+    ) -> Result<()> {
+        let expression = &ctx.accounts.derivation.expression;
+        let sources = expression.sources();
+        if sources.len() != ctx.remaining_accounts.len() {
+            return Err(Error::InvalidDerivation.into());
+        }
+
+        let mut values = Vec::with_capacity(sources.len());
+        let mut oldest = i64::MAX;
+        let mut newest_slot = 0;
+        for (info, source) in ctx.remaining_accounts.iter().zip(sources) {
+            if info.key() != source {
+                return Err(Error::InvalidDerivation.into());
+            }
+            let source = Account::<Oracle>::try_from(info)?;
+            values.push((
+                source.value.ok_or(Error::EmptyOracle)?,
+                source.exponent,
+            ));
+            oldest = oldest.min(source.updated_at);
+            newest_slot = newest_slot.max(source.slot);
+        }
+
         let oracle = &mut ctx.accounts.oracle;
-        oracle.value = value;
-        oracle.updated_at = Clock::get()?.unix_timestamp;
+        if oracle.value.is_some() && newest_slot < oracle.slot {
+            msg!("No source was updated since the last refresh");
+            return Err(Error::InvalidState.into());
+        }
+        let value = expression
+            .evaluate(&values, oracle.exponent)
+            .ok_or(Error::Overflow)?;
+
+        let clock = Clock::get()?;
+        record_value(
+            oracle,
+            Some(value),
+            None,
+            // a derived value is only as recent as its oldest source
+            &Clock {
+                unix_timestamp: oldest,
+                ..clock
+            },
+        );
+
+        Ok(())
+    }
+
+    pub fn close_derivation(ctx: Context<CloseDerivation>) -> Result<()> {
+        ctx.accounts.oracle.mode = FeedMode::Direct;
+
+        Ok(())
+    }
+
+    pub fn init_metadata(
+        ctx: Context<InitMetadata>,
+        info: FeedMetadata,
+    ) -> Result<()> {
+        info.validate()?;
+        let metadata = &mut ctx.accounts.metadata;
+        metadata.oracle = ctx.accounts.oracle.key();
+        metadata.info = info;
 
         Ok(())
     }
 
-    pub fn delete(_ctx: Context<Delete>) -> Result<()> {
+    pub fn set_metadata(
+        ctx: Context<SetMetadata>,
+        info: FeedMetadata,
+    ) -> Result<()> {
+        info.validate()?;
+        ctx.accounts.metadata.info = info;
+
+        Ok(())
+    }
+
+    pub fn close_metadata(_ctx: Context<CloseMetadata>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn migrate(ctx: Context<Migrate>) -> Result<()> {
+        let account = ctx.accounts.oracle.to_account_info();
+        let version = Oracle::layout_version(&account.try_borrow_data()?);
+        if version == Some(Oracle::VERSION) {
+            msg!("Oracle already uses layout version {}", Oracle::VERSION);
+            return Err(Error::InvalidState.into());
+        }
+        let mut oracle =
+            Oracle::from_account_data(&account.try_borrow_data()?)?;
+        // larger histories of older layouts no longer fit into the account
+        oracle.shrink_history(Oracle::MAX_HISTORY_CAPACITY);
+
+        let space = Oracle::space(oracle.name.len(), oracle.history_capacity);
+        let shortfall = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(account.lamports());
+        if shortfall > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: account.clone(),
+                    },
+                ),
+                shortfall,
+            )?;
+        }
+        account.realloc(space, true)?;
+        oracle.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+
+        Ok(())
+    }
+
+    pub fn delete(ctx: Context<Delete>) -> Result<()> {
+        emit!(OracleDeleted {
+            oracle: ctx.accounts.oracle.key(),
+            owner: ctx.accounts.oracle.owner,
+        });
+
+        Ok(())
+    }
+
+    pub fn reap(ctx: Context<Reap>) -> Result<()> {
+        let reap_after_secs = ctx.accounts.config.reap_after_secs;
+        let idle_secs = Clock::get()?
+            .unix_timestamp
+            .saturating_sub(ctx.accounts.oracle.updated_at);
+        if reap_after_secs == 0 || idle_secs < reap_after_secs {
+            return Err(Error::OracleNotAbandoned.into());
+        }
+
+        if let Some(parent) = &mut ctx.accounts.parent {
+            parent.children =
+                parent.children.checked_sub(1).ok_or(Error::Overflow)?;
+        }
+        let metadata = ctx.accounts.metadata.to_account_info();
+        if metadata.owner == &crate::ID {
+            close_account(&metadata, &ctx.accounts.treasury)?;
+        }
+
+        emit!(OracleDeleted {
+            oracle: ctx.accounts.oracle.key(),
+            owner: ctx.accounts.oracle.owner,
+        });
+
+        Ok(())
+    }
+
+    pub fn create_fast(
+        ctx: Context<CreateFast>,
+        name: String,
+        exponent: i8,
+    ) -> Result<()> {
+        validate_name(&name)?;
+        charge_fee(
+            &ctx.accounts.payer,
+            &ctx.accounts.treasury,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.create_fee,
+        )?;
+
+        let mut oracle = ctx.accounts.oracle.load_init()?;
+        oracle.owner = ctx.accounts.owner.key();
+        oracle.name_seed = name_seed(&name);
+        oracle.exponent = exponent;
+
+        Ok(())
+    }
+
+    /// Writes a fast oracle in place. Unlike `update` no event is emitted,
+    /// the account itself is the record of the value.
+    pub fn update_fast(
+        ctx: Context<UpdateFast>,
+        value: Option<i128>,
+        confidence: Option<u128>,
+    ) -> Result<()> {
+        charge_fee(
+            &ctx.accounts.owner,
+            &ctx.accounts.treasury,
+            &ctx.accounts.system_program,
+            ctx.accounts.config.update_fee,
+        )?;
+        let mut oracle = ctx.accounts.oracle.load_mut()?;
+        oracle.record(value, confidence, &Clock::get()?);
+
+        Ok(())
+    }
+
+    pub fn delete_fast(_ctx: Context<DeleteFast>) -> Result<()> {
         Ok(())
     }
 }
 
+/// Initializes a freshly created root feed in `namespace`.
+fn init_root(
+    oracle: &mut Account<Oracle>,
+    feed: Feed,
+    namespace: Pubkey,
+) -> Result<()> {
+    oracle.version = Oracle::VERSION;
+    oracle.owner = feed.owner;
+    oracle.name_seed = name_seed(&feed.name);
+    oracle.name = feed.name;
+    oracle.namespace = namespace;
+    oracle.exponent = feed.exponent;
+    oracle.policy = feed.policy;
+    oracle.history_capacity = feed.history_capacity;
+    oracle.record(feed.value, None, &Clock::get()?);
+
+    emit!(OracleCreated {
+        oracle: oracle.key(),
+        owner: oracle.owner,
+        name: oracle.name.clone(),
+        value: oracle.value,
+        timestamp: oracle.updated_at,
+    });
+
+    Ok(())
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.len() > Oracle::MAX_NAME_LEN {
+        msg!(
+            "Oracle name must be between 1 and {} bytes",
+            Oracle::MAX_NAME_LEN
+        );
+        return Err(ProgramError::InvalidArgument.into());
+    }
+    if name.contains('.') {
+        msg!("Oracle names cannot contain dots, they separate path segments");
+        return Err(ProgramError::InvalidArgument.into());
+    }
+    Ok(())
+}
+
+/// Closes an account owned by this program that is not deserialized by the
+/// instruction, its rent goes to `recipient`.
+fn close_account<'info>(
+    account: &AccountInfo<'info>,
+    recipient: &AccountInfo<'info>,
+) -> Result<()> {
+    let lamports = recipient
+        .lamports()
+        .checked_add(account.lamports())
+        .ok_or(Error::Overflow)?;
+    **recipient.try_borrow_mut_lamports()? = lamports;
+    **account.try_borrow_mut_lamports()? = 0;
+    account.assign(&system_program::ID);
+    account.realloc(0, false)?;
+    Ok(())
+}
+
+/// Transfers `fee` lamports from `payer` to the protocol treasury.
+fn charge_fee<'info>(
+    payer: &Signer<'info>,
+    treasury: &UncheckedAccount<'info>,
+    system: &Program<'info, System>,
+    fee: u64,
+) -> Result<()> {
+    if fee == 0 {
+        return Ok(());
+    }
+    system_program::transfer(
+        CpiContext::new(
+            system.to_account_info(),
+            system_program::Transfer {
+                from: payer.to_account_info(),
+                to: treasury.to_account_info(),
+            },
+        ),
+        fee,
+    )
+}
+
+/// Writes a new value to the oracle and emits the corresponding event.
+fn record_value(
+    oracle: &mut Account<Oracle>,
+    value: Option<i128>,
+    confidence: Option<u128>,
+    clock: &Clock,
+) {
+    let old_value = oracle.value;
+    let old_timestamp = oracle.updated_at;
+    oracle.record(value, confidence, clock);
+
+    emit!(OracleUpdated {
+        oracle: oracle.key(),
+        old_value,
+        new_value: oracle.value,
+        old_timestamp,
+        new_timestamp: oracle.updated_at,
+        slot: oracle.slot,
+        round_id: oracle.round_id,
+    });
+}
+
 #[derive(Accounts)]
-#[instruction(feed: Oracle)]
-pub struct Create<'info> {
+pub struct InitConfig<'info> {
     #[account(mut)]
+    authority: Signer<'info>,
+    #[account(
+        init,
+        payer = authority,
+        space = Config::SPACE,
+        seeds = [Config::SEED],
+        bump
+    )]
+    config: Account<'info, Config>,
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key())
+    )]
+    program: Program<'info, crate::program::VvtecOnchain>,
+    #[account(
+        constraint = program_data.upgrade_authority_address
+            == Some(authority.key()) @ Error::OwnerMismatch
+    )]
+    program_data: Account<'info, ProgramData>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Configure<'info> {
+    admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [Config::SEED],
+        bump,
+        has_one = admin @ Error::OwnerMismatch,
+    )]
+    config: Account<'info, Config>,
+}
+
+/// Creates a root feed in the global namespace, only the admin can create
+/// them while it is [`Config::curated`].
+#[derive(Accounts)]
+#[instruction(feed: Feed)]
+pub struct Create<'info> {
+    #[account(
+        mut,
+        constraint = !config.curated || payer.key() == config.admin
+            @ Error::CuratedNamespace,
+    )]
     payer: Signer<'info>,
     #[account(
         init, 
         payer = payer, 
-        space = 8 + 89, 
-        seeds = [&feed.name],
+        space = Oracle::space(feed.name.len(), feed.history_capacity),
+        seeds = [&name_seed(&feed.name)],
+        bump
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+    /// CHECK: only receives the fee, must match the configured treasury.
+    #[account(mut, address = config.treasury)]
+    treasury: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+}
+
+/// Creates a root feed in the namespace of its owner, who must sign.
+#[derive(Accounts)]
+#[instruction(feed: Feed)]
+pub struct CreateNamespaced<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+    #[account(address = feed.owner @ Error::OwnerMismatch)]
+    owner: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = Oracle::space(feed.name.len(), feed.history_capacity),
+        seeds = [
+            Oracle::SEED,
+            feed.owner.as_ref(),
+            &name_seed(&feed.name),
+        ],
         bump
     )]
     oracle: Account<'info, Oracle>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+    /// CHECK: only receives the fee, must match the configured treasury.
+    #[account(mut, address = config.treasury)]
+    treasury: UncheckedAccount<'info>,
     system_program: Program<'info, System>,
 }
 
 #[derive(Debug, AnchorSerialize, AnchorDeserialize)]
 pub struct Feed {
     pub owner: Pubkey,
-    pub name: [u8; 32],
-    pub value: Option<u128>,
+    pub name: String,
+    pub value: Option<i128>,
+    pub exponent: i8,
+    pub policy: UpdatePolicy,
+    pub history_capacity: u16,
+}
+
+impl Feed {
+    fn validate(&self) -> Result<()> {
+        validate_name(&self.name)?;
+        if self.history_capacity > Oracle::MAX_HISTORY_CAPACITY {
+            msg!(
+                "Oracle history capacity must not exceed {}",
+                Oracle::MAX_HISTORY_CAPACITY
+            );
+            return Err(ProgramError::InvalidArgument.into());
+        }
+        Ok(())
+    }
 }
 
 #[derive(Accounts)]
-pub struct Update<'info> {
+#[instruction(feed: Feed)]
+pub struct AttachChild<'info> {
     #[account(mut)]
+    payer: Signer<'info>,
     owner: Signer<'info>,
     #[account(
         mut,
-        seeds = [&oracle.name],
+        seeds = [parent.prefix_seed(), parent.scope_seed(), &parent.name_seed],
         bump,
         has_one = owner,
     )]
-    oracle: Account<'info, Oracle>,
+    parent: Account<'info, Oracle>,
+    #[account(
+        init,
+        payer = payer,
+        space = Oracle::space(feed.name.len(), feed.history_capacity),
+        seeds = [parent.key().as_ref(), &name_seed(&feed.name)],
+        bump
+    )]
+    child: Account<'info, Oracle>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+    /// CHECK: only receives the fee, must match the configured treasury.
+    #[account(mut, address = config.treasury)]
+    treasury: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct Delete<'info> {
+pub struct DetachChild<'info> {
     #[account(mut)]
     owner: Signer<'info>,
     #[account(
         mut,
-        seeds = [&oracle.name],
+        seeds = [parent.prefix_seed(), parent.scope_seed(), &parent.name_seed],
         bump,
         has_one = owner,
-        close = owner,
+    )]
+    parent: Account<'info, Oracle>,
+    #[account(
+        mut,
+        seeds = [parent.key().as_ref(), &child.name_seed],
+        bump,
+        constraint = child.parent == parent.key() @ Error::ParentMismatch,
+        constraint = child.children == 0 @ Error::OracleHasChildren,
+        constraint = child.mode == FeedMode::Direct @ Error::AggregatedOracle,
+        close = child_owner,
+    )]
+    child: Account<'info, Oracle>,
+    /// CHECK: only receives the rent of the closed child, must be its owner.
+    #[account(mut, address = child.owner @ Error::OwnerMismatch)]
+    child_owner: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Update<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        has_one = owner,
+        constraint = oracle.mode == FeedMode::Direct @ Error::AggregatedOracle,
     )]
     oracle: Account<'info, Oracle>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+    /// CHECK: only receives the fee, must match the configured treasury.
+    #[account(mut, address = config.treasury)]
+    treasury: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+}
+
+/// A new value for one of the oracles passed to `batch_update`.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct FeedUpdate {
+    pub value: Option<i128>,
+    pub confidence: Option<u128>,
+}
+
+/// Writes many oracles of the same owner at once. The oracles are passed as
+/// writable remaining accounts, in the same order as the updates.
+#[derive(Accounts)]
+pub struct BatchUpdate<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+    /// CHECK: only receives the fee, must match the configured treasury.
+    #[account(mut, address = config.treasury)]
+    treasury: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CheckHealth<'info> {
+    #[account(
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump
+    )]
+    oracle: Account<'info, Oracle>,
+}
+
+#[derive(Accounts)]
+pub struct OpenRequest<'info> {
+    #[account(mut)]
+    requester: Signer<'info>,
+    #[account(
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        init,
+        payer = requester,
+        space = Request::SPACE,
+        seeds = [Request::SEED, config.next_request_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    request: Account<'info, Request>,
+    #[account(
+        mut,
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+    /// CHECK: only receives the fee, must match the configured treasury.
+    #[account(mut, address = config.treasury)]
+    treasury: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FulfillRequest<'info> {
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        has_one = owner,
+        constraint = oracle.mode == FeedMode::Direct @ Error::AggregatedOracle,
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        mut,
+        seeds = [Request::SEED, request.id.to_le_bytes().as_ref()],
+        bump,
+        has_one = oracle,
+        constraint = !request.is_fulfilled() @ Error::RequestFulfilled,
+    )]
+    request: Account<'info, Request>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRequest<'info> {
+    #[account(mut)]
+    requester: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [Request::SEED, request.id.to_le_bytes().as_ref()],
+        bump,
+        has_one = requester,
+        close = requester,
+    )]
+    request: Account<'info, Request>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitSigned<'info> {
+    #[account(mut)]
+    relayer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        constraint = oracle.mode == FeedMode::Direct @ Error::AggregatedOracle,
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+    /// CHECK: only receives the fee, must match the configured treasury.
+    #[account(mut, address = config.treasury)]
+    treasury: UncheckedAccount<'info>,
+    /// CHECK: the instructions sysvar, checked by its address.
+    #[account(address = sysvar::instructions::ID)]
+    instructions: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeOwner<'info> {
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        has_one = owner,
+    )]
+    oracle: Account<'info, Oracle>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptOwner<'info> {
+    new_owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        constraint = oracle.pending_owner == Some(new_owner.key())
+            @ Error::OwnerMismatch,
+    )]
+    oracle: Account<'info, Oracle>,
+}
+
+#[derive(Accounts)]
+#[instruction(publishers: Vec<Pubkey>)]
+pub struct InitAggregator<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        has_one = owner,
+        constraint = oracle.mode == FeedMode::Direct @ Error::AggregatedOracle,
+        constraint = publishers.len() <= Aggregator::MAX_PUBLISHERS,
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        init,
+        payer = owner,
+        space = Aggregator::space(publishers.len()),
+        seeds = [Aggregator::SEED, oracle.key().as_ref()],
+        bump
+    )]
+    aggregator: Account<'info, Aggregator>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Submit<'info> {
+    publisher: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        constraint = oracle.mode == FeedMode::Aggregate,
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        mut,
+        seeds = [Aggregator::SEED, oracle.key().as_ref()],
+        bump,
+        has_one = oracle,
+    )]
+    aggregator: Account<'info, Aggregator>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct CloseAggregator<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        has_one = owner,
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        mut,
+        seeds = [Aggregator::SEED, oracle.key().as_ref()],
+        bump,
+        has_one = oracle,
+        close = owner,
+    )]
+    aggregator: Account<'info, Aggregator>,
+}
+
+#[derive(Accounts)]
+#[instruction(expression: Expression)]
+pub struct InitDerivation<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        has_one = owner,
+        constraint = oracle.mode == FeedMode::Direct @ Error::AggregatedOracle,
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        init,
+        payer = owner,
+        space = Derivation::space(expression.sources().len()),
+        seeds = [Derivation::SEED, oracle.key().as_ref()],
+        bump
+    )]
+    derivation: Account<'info, Derivation>,
+    system_program: Program<'info, System>,
+}
+
+/// Recomputes a derived oracle. The sources are passed as remaining
+/// accounts, in the order of [`Expression::sources`].
+#[derive(Accounts)]
+pub struct Refresh<'info> {
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        constraint = oracle.mode == FeedMode::Derived,
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        seeds = [Derivation::SEED, oracle.key().as_ref()],
+        bump,
+        has_one = oracle,
+    )]
+    derivation: Account<'info, Derivation>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct CloseDerivation<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        has_one = owner,
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        mut,
+        seeds = [Derivation::SEED, oracle.key().as_ref()],
+        bump,
+        has_one = oracle,
+        close = owner,
+    )]
+    derivation: Account<'info, Derivation>,
+}
+
+#[derive(Accounts)]
+pub struct InitMetadata<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        has_one = owner,
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        init,
+        payer = owner,
+        space = Metadata::SPACE,
+        seeds = [Metadata::SEED, oracle.key().as_ref()],
+        bump
+    )]
+    metadata: Account<'info, Metadata>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMetadata<'info> {
+    owner: Signer<'info>,
+    #[account(
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        has_one = owner,
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        mut,
+        seeds = [Metadata::SEED, oracle.key().as_ref()],
+        bump,
+        has_one = oracle,
+    )]
+    metadata: Account<'info, Metadata>,
+}
+
+#[derive(Accounts)]
+pub struct CloseMetadata<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        has_one = owner,
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        mut,
+        seeds = [Metadata::SEED, oracle.key().as_ref()],
+        bump,
+        has_one = oracle,
+        close = owner,
+    )]
+    metadata: Account<'info, Metadata>,
+}
+
+#[derive(Accounts)]
+pub struct Migrate<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+    /// CHECK: older layouts cannot be deserialized as `Oracle`, the account
+    /// data is decoded by `Oracle::from_account_data` instead.
+    #[account(mut, owner = crate::ID)]
+    oracle: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+}
+
+/// Closes a root feed, its rent goes to `recipient`.
+#[derive(Accounts)]
+pub struct Delete<'info> {
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        has_one = owner,
+        constraint = oracle.is_root() @ Error::ParentMismatch,
+        constraint = oracle.children == 0 @ Error::OracleHasChildren,
+        constraint = oracle.mode == FeedMode::Direct @ Error::AggregatedOracle,
+        close = recipient,
+    )]
+    oracle: Account<'info, Oracle>,
+    /// CHECK: only receives the rent of the closed oracle.
+    #[account(mut)]
+    recipient: UncheckedAccount<'info>,
+}
+
+/// Closes a feed that was not written for [`Config::reap_after_secs`], such
+/// as a feed whose owner lost its key. The rent goes to the treasury.
+///
+/// Child feeds are detached from their `parent`, which must be passed for
+/// them. Feeds with children are reaped once their children are.
+///
+/// The metadata account of the feed is closed along with it if it exists.
+/// The aggregator and derivation accounts are closed too, they are required
+/// for feeds in the matching [`FeedMode`].
+#[derive(Accounts)]
+pub struct Reap<'info> {
+    admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        constraint = oracle.is_root() || parent.is_some() @ Error::ParentMismatch,
+        constraint = oracle.children == 0 @ Error::OracleHasChildren,
+        constraint = oracle.mode != FeedMode::Aggregate
+            || aggregator.is_some() @ Error::AggregatedOracle,
+        constraint = oracle.mode != FeedMode::Derived
+            || derivation.is_some() @ Error::AggregatedOracle,
+        close = treasury,
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(mut, address = oracle.parent @ Error::ParentMismatch)]
+    parent: Option<Account<'info, Oracle>>,
+    /// CHECK: the metadata PDA of the oracle, closed if it was initialized.
+    #[account(mut, seeds = [Metadata::SEED, oracle.key().as_ref()], bump)]
+    metadata: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [Aggregator::SEED, oracle.key().as_ref()],
+        bump,
+        close = treasury,
+    )]
+    aggregator: Option<Account<'info, Aggregator>>,
+    #[account(
+        mut,
+        seeds = [Derivation::SEED, oracle.key().as_ref()],
+        bump,
+        close = treasury,
+    )]
+    derivation: Option<Account<'info, Derivation>>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        has_one = admin @ Error::OwnerMismatch,
+    )]
+    config: Account<'info, Config>,
+    /// CHECK: only receives the rent, must match the configured treasury.
+    #[account(mut, address = config.treasury)]
+    treasury: UncheckedAccount<'info>,
+}
+
+/// Creates a fast oracle in the namespace of its owner, who must sign.
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct CreateFast<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+    owner: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = FastOracle::SPACE,
+        seeds = [FastOracle::SEED, owner.key().as_ref(), &name_seed(&name)],
+        bump
+    )]
+    oracle: AccountLoader<'info, FastOracle>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+    /// CHECK: only receives the fee, must match the configured treasury.
+    #[account(mut, address = config.treasury)]
+    treasury: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateFast<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(mut, has_one = owner)]
+    oracle: AccountLoader<'info, FastOracle>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+    /// CHECK: only receives the fee, must match the configured treasury.
+    #[account(mut, address = config.treasury)]
+    treasury: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DeleteFast<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(mut, has_one = owner, close = owner)]
+    oracle: AccountLoader<'info, FastOracle>,
 }
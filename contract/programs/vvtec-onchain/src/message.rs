@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+/// A value of a feed signed off-chain by the owner of the feed.
+///
+/// Anyone can relay a signed value to the program with `submit_signed`, which
+/// only accepts it along with an ed25519 program instruction that verifies the
+/// owner signature over [`PriceMessage::to_bytes`].
+#[derive(Debug, Clone, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct PriceMessage {
+    /// The name seed of the feed, see
+    /// [`name_seed`](crate::state::name_seed).
+    pub name: [u8; 32],
+
+    /// The parent of the feed, or the namespace of a root feed, see
+    /// [`Oracle::scope`](crate::state::Oracle::scope).
+    pub parent: Pubkey,
+
+    pub value: Option<i128>,
+
+    pub confidence: Option<u128>,
+
+    /// A unix timestamp of the time the value was observed. It must be newer
+    /// than the current value of the feed, so a message cannot be replayed.
+    pub timestamp: i64,
+}
+
+impl PriceMessage {
+    /// Precedes the message in the signed bytes, so these signatures are not
+    /// valid for anything else.
+    pub const PREFIX: &'static [u8] = b"vvtec price message v1";
+
+    /// The bytes signed by the owner: [`PriceMessage::PREFIX`] followed by the
+    /// borsh encoding of the message.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Self::PREFIX.to_vec();
+        self.serialize(&mut bytes)
+            .expect("serializing to a vec never fails");
+        bytes
+    }
+}
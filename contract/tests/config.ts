@@ -0,0 +1,38 @@
+import * as anchor from "@project-serum/anchor";
+import { Program, BN } from "@project-serum/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { VvtecOnchain } from "../target/types/vvtec_onchain";
+
+const BPF_LOADER_UPGRADEABLE = new PublicKey(
+    "BPFLoaderUpgradeab1e11111111111111111111111"
+);
+
+// Initializes the program config on first use, with the provider wallet as
+// admin and treasury and without fees. Returns the accounts needed by the
+// instructions that check the config.
+export const ensureConfig = async (program: Program<VvtecOnchain>) => {
+    const provider = program.provider as anchor.AnchorProvider;
+    const [config] = await PublicKey.findProgramAddress(
+        [Buffer.from("config")],
+        program.programId
+    );
+
+    let configAcc = await program.account.config.fetchNullable(config);
+    if (configAcc == null) {
+        const [programData] = await PublicKey.findProgramAddress(
+            [program.programId.toBuffer()],
+            BPF_LOADER_UPGRADEABLE
+        );
+        await program.methods
+            .initConfig(provider.wallet.publicKey, new BN(0), new BN(0), new BN(0))
+            .accounts({
+                config,
+                program: program.programId,
+                programData,
+            })
+            .rpc();
+        configAcc = await program.account.config.fetch(config);
+    }
+
+    return { config, treasury: configAcc.treasury };
+};
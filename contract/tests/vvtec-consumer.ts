@@ -0,0 +1,154 @@
+import assert from 'assert';
+import * as anchor from "@project-serum/anchor";
+import { Program, BN } from "@project-serum/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { VvtecOnchain } from "../target/types/vvtec_onchain";
+import { VvtecConsumer } from "../target/types/vvtec_consumer";
+import { ensureConfig } from "./config";
+
+describe("vvtec-consumer", () => {
+    const provider = anchor.AnchorProvider.env();
+    anchor.setProvider(provider);
+
+    const vvtec = anchor.workspace.VvtecOnchain as Program<VvtecOnchain>;
+    const program = anchor.workspace.VvtecConsumer as Program<VvtecConsumer>;
+
+    const feedName = Buffer.alloc(32);
+    feedName.fill("consumed");
+
+    let configAccounts: { config: PublicKey, treasury: PublicKey };
+
+    before(async () => {
+        configAccounts = await ensureConfig(vvtec);
+    });
+
+    const readingOf = async (oracle: PublicKey) => {
+        const [reading] = await PublicKey.findProgramAddress(
+            [Buffer.from("reading"), oracle.toBuffer()],
+            program.programId
+        );
+        return reading;
+    };
+
+    it("Reads a fresh feed!", async () => {
+        const [oracle] = await PublicKey.findProgramAddress(
+            [feedName],
+            vvtec.programId
+        );
+        await vvtec.methods
+            .create({
+                name: [...feedName],
+                owner: provider.wallet.publicKey,
+                value: new BN(1000),
+                exponent: -2,
+                policy: { heartbeatSecs: 0, deviationBps: 0 },
+                historyCapacity: 0,
+            })
+            .accounts({
+                oracle,
+                ...configAccounts,
+            })
+            .rpc();
+        await vvtec.methods
+            .update(new BN(1000), new BN(5))
+            .accounts({
+                oracle,
+                ...configAccounts,
+            })
+            .rpc();
+
+        const reading = await readingOf(oracle);
+        await program.methods
+            .readPrice(new BN(60), 100)
+            .accounts({
+                oracle,
+                reading,
+            })
+            .rpc();
+
+        const readingAcc = await program.account.reading.fetch(reading);
+        assert.ok(readingAcc.value.eq(new BN(1000)));
+        assert.equal(readingAcc.exponent, -2);
+    });
+
+    it("Rejects a wide confidence interval!", async () => {
+        const [oracle] = await PublicKey.findProgramAddress(
+            [feedName],
+            vvtec.programId
+        );
+        const reading = await readingOf(oracle);
+
+        // 5 is 50 bps of 1000
+        await assert.rejects(
+            program.methods
+                .readPrice(new BN(60), 10)
+                .accounts({
+                    oracle,
+                    reading,
+                })
+                .rpc(),
+            /ConfidenceTooWide/
+        );
+    });
+
+    it("Rejects a stale feed!", async () => {
+        const [oracle] = await PublicKey.findProgramAddress(
+            [feedName],
+            vvtec.programId
+        );
+        const reading = await readingOf(oracle);
+
+        await new Promise((resolve) => setTimeout(resolve, 2000));
+        await assert.rejects(
+            program.methods
+                .readPrice(new BN(1), 100)
+                .accounts({
+                    oracle,
+                    reading,
+                })
+                .rpc(),
+            /StaleOracle/
+        );
+    });
+
+    it("Rejects accounts not owned by vvtec!", async () => {
+        const fake = Keypair.generate();
+        await provider.sendAndConfirm(
+            new anchor.web3.Transaction().add(
+                SystemProgram.createAccount({
+                    fromPubkey: provider.wallet.publicKey,
+                    newAccountPubkey: fake.publicKey,
+                    lamports: await provider.connection.getMinimumBalanceForRentExemption(256),
+                    space: 256,
+                    programId: SystemProgram.programId,
+                })
+            ),
+            [fake]
+        );
+        const reading = await readingOf(fake.publicKey);
+
+        await assert.rejects(
+            program.methods
+                .readPrice(new BN(60), 100)
+                .accounts({
+                    oracle: fake.publicKey,
+                    reading,
+                })
+                .rpc(),
+            /OwnerMismatch/
+        );
+    });
+
+    after(async () => {
+        const [oracle] = await PublicKey.findProgramAddress(
+            [feedName],
+            vvtec.programId
+        );
+        await vvtec.methods
+            .delete()
+            .accounts({
+                oracle,
+            })
+            .rpc();
+    });
+});
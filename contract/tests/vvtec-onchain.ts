@@ -1,8 +1,15 @@
 import assert from 'assert';
+import { createHash } from "crypto";
 import * as anchor from "@project-serum/anchor";
 import { Program, BN } from "@project-serum/anchor";
-import { PublicKey } from "@solana/web3.js";
+import {
+    Ed25519Program,
+    Keypair,
+    LAMPORTS_PER_SOL,
+    PublicKey,
+} from "@solana/web3.js";
 import { VvtecOnchain } from "../target/types/vvtec_onchain";
+import { ensureConfig } from "./config";
 
 describe("vvtec-onchain", () => {
     const provider = anchor.AnchorProvider.env();
@@ -10,6 +17,12 @@ describe("vvtec-onchain", () => {
 
     const program = anchor.workspace.VvtecOnchain as Program<VvtecOnchain>;
 
+    let configAccounts: { config: PublicKey, treasury: PublicKey };
+
+    before(async () => {
+        configAccounts = await ensureConfig(program);
+    });
+
     it("Create!", async () => {
         const feedName = Buffer.alloc(32);
         feedName.fill("test");
@@ -21,12 +34,16 @@ describe("vvtec-onchain", () => {
         );
         const tx = await program.methods
             .create({
-                name: [...feedName],
+                name: feedName.toString(),
                 owner: provider.wallet.publicKey,
                 value,
+                exponent: -2,
+                policy: { heartbeatSecs: 0, deviationBps: 0 },
+                historyCapacity: 2,
             })
             .accounts({
                 oracle,
+                ...configAccounts,
             })
             .rpc();
         console.log("Your transaction signature", tx);
@@ -34,8 +51,13 @@ describe("vvtec-onchain", () => {
         let oracleAcc = await program.account.oracle.fetch(oracle)
 
         assert.ok(oracleAcc.owner.equals(provider.wallet.publicKey))
-        assert.ok(feedName.equals(Buffer.from(oracleAcc.name)))
+        assert.ok(feedName.equals(Buffer.from(oracleAcc.nameSeed)))
         assert.ok(oracleAcc.value.eq(value))
+        assert.equal(oracleAcc.version, 8)
+        assert.ok(oracleAcc.roundId.eq(new BN(1)))
+        assert.equal(oracleAcc.exponent, -2)
+        assert.equal(oracleAcc.historyCapacity, 2)
+        assert.equal(oracleAcc.history.length, 1)
     });
 
     it("Update!", async () => {
@@ -48,9 +70,10 @@ describe("vvtec-onchain", () => {
             program.programId
         );
         const tx = await program.methods
-            .update(value)
+            .update(value, null)
             .accounts({
                 oracle,
+                ...configAccounts,
             })
             .rpc();
         console.log("Your transaction signature", tx);
@@ -58,8 +81,223 @@ describe("vvtec-onchain", () => {
         let oracleAcc = await program.account.oracle.fetch(oracle)
 
         assert.ok(oracleAcc.owner.equals(provider.wallet.publicKey))
-        assert.ok(feedName.equals(Buffer.from(oracleAcc.name)))
+        assert.ok(feedName.equals(Buffer.from(oracleAcc.nameSeed)))
         assert.ok(oracleAcc.value == value)
+        assert.equal(oracleAcc.history.length, 2)
+        assert.ok(oracleAcc.history[1].value == value)
+        assert.ok(oracleAcc.roundId.eq(new BN(2)))
+        assert.ok(oracleAcc.slot.eq(oracleAcc.history[1].slot))
+        assert.ok(oracleAcc.history[1].roundId.eq(oracleAcc.roundId))
+    });
+
+    it("Update wraps history!", async () => {
+        const feedName = Buffer.alloc(32);
+        feedName.fill("test");
+        const value = new BN(-7)
+
+        const [oracle] = await PublicKey.findProgramAddress(
+            [feedName],
+            program.programId
+        );
+        await program.methods
+            .update(value, new BN(3))
+            .accounts({
+                oracle,
+                ...configAccounts,
+            })
+            .rpc();
+
+        let oracleAcc = await program.account.oracle.fetch(oracle)
+
+        assert.ok(oracleAcc.confidence.eq(new BN(3)))
+        assert.equal(oracleAcc.history.length, 2)
+        assert.equal(oracleAcc.historyHead, 1)
+        assert.ok(oracleAcc.history[0].value.eq(value))
+    });
+
+    it("Accumulates value over time!", async () => {
+        const feedName = Buffer.alloc(32);
+        feedName.fill("twap");
+
+        const [oracle] = await PublicKey.findProgramAddress(
+            [feedName],
+            program.programId
+        );
+        await program.methods
+            .create({
+                name: feedName.toString(),
+                owner: provider.wallet.publicKey,
+                value: new BN(5),
+                exponent: 0,
+                policy: { heartbeatSecs: 0, deviationBps: 0 },
+                historyCapacity: 0,
+            })
+            .accounts({
+                oracle,
+                ...configAccounts,
+            })
+            .rpc();
+        const created = await program.account.oracle.fetch(oracle);
+        assert.ok(created.cumulativeValue.isZero());
+
+        await new Promise((resolve) => setTimeout(resolve, 2000));
+        await program.methods
+            .update(new BN(0), null)
+            .accounts({
+                oracle,
+                ...configAccounts,
+            })
+            .rpc();
+
+        const updated = await program.account.oracle.fetch(oracle);
+        const held = updated.updatedAt.sub(created.updatedAt);
+        assert.ok(held.gtn(0));
+        assert.ok(updated.cumulativeValue.eq(held.muln(5)));
+
+        await program.methods
+            .delete()
+            .accounts({
+                oracle,
+                recipient: provider.wallet.publicKey,
+            })
+            .rpc();
+    });
+
+    it("Transfer ownership!", async () => {
+        const feedName = Buffer.alloc(32);
+        feedName.fill("test");
+        const newOwner = Keypair.generate();
+
+        const [oracle] = await PublicKey.findProgramAddress(
+            [feedName],
+            program.programId
+        );
+        await program.methods
+            .proposeOwner(newOwner.publicKey)
+            .accounts({
+                oracle,
+            })
+            .rpc();
+
+        let oracleAcc = await program.account.oracle.fetch(oracle)
+        assert.ok(oracleAcc.owner.equals(provider.wallet.publicKey))
+        assert.ok(oracleAcc.pendingOwner.equals(newOwner.publicKey))
+
+        await program.methods
+            .acceptOwner()
+            .accounts({
+                newOwner: newOwner.publicKey,
+                oracle,
+            })
+            .signers([newOwner])
+            .rpc();
+
+        oracleAcc = await program.account.oracle.fetch(oracle)
+        assert.ok(oracleAcc.owner.equals(newOwner.publicKey))
+        assert.ok(oracleAcc.pendingOwner == null)
+
+        // hand the feed back for the remaining tests
+        await program.methods
+            .proposeOwner(provider.wallet.publicKey)
+            .accounts({
+                owner: newOwner.publicKey,
+                oracle,
+            })
+            .signers([newOwner])
+            .rpc();
+        await program.methods
+            .acceptOwner()
+            .accounts({
+                oracle,
+            })
+            .rpc();
+    });
+
+    it("Attach child!", async () => {
+        const feedName = Buffer.alloc(32);
+        feedName.fill("test");
+        const childName = Buffer.alloc(32);
+        childName.fill("child");
+
+        const [parent] = await PublicKey.findProgramAddress(
+            [feedName],
+            program.programId
+        );
+        const [child] = await PublicKey.findProgramAddress(
+            [parent.toBuffer(), childName],
+            program.programId
+        );
+        await program.methods
+            .attachChild({
+                name: childName.toString(),
+                owner: provider.wallet.publicKey,
+                value: new BN(1),
+                exponent: 0,
+                policy: { heartbeatSecs: 0, deviationBps: 0 },
+                historyCapacity: 0,
+            })
+            .accounts({
+                parent,
+                child,
+                ...configAccounts,
+            })
+            .rpc();
+
+        let parentAcc = await program.account.oracle.fetch(parent)
+        let childAcc = await program.account.oracle.fetch(child)
+
+        assert.equal(parentAcc.children, 1)
+        assert.ok(childAcc.parent.equals(parent))
+        assert.ok(childAcc.value.eq(new BN(1)))
+    });
+
+    it("Delete with children fails!", async () => {
+        const feedName = Buffer.alloc(32);
+        feedName.fill("test");
+
+        const [oracle] = await PublicKey.findProgramAddress(
+            [feedName],
+            program.programId
+        );
+        await assert.rejects(
+            program.methods
+                .delete()
+                .accounts({
+                    oracle,
+                    recipient: provider.wallet.publicKey,
+                })
+                .rpc()
+        );
+    });
+
+    it("Detach child!", async () => {
+        const feedName = Buffer.alloc(32);
+        feedName.fill("test");
+        const childName = Buffer.alloc(32);
+        childName.fill("child");
+
+        const [parent] = await PublicKey.findProgramAddress(
+            [feedName],
+            program.programId
+        );
+        const [child] = await PublicKey.findProgramAddress(
+            [parent.toBuffer(), childName],
+            program.programId
+        );
+        await program.methods
+            .detachChild()
+            .accounts({
+                parent,
+                child,
+                childOwner: provider.wallet.publicKey,
+            })
+            .rpc();
+
+        let parentAcc = await program.account.oracle.fetch(parent)
+        let childAcc = await program.account.oracle.fetchNullable(child)
+
+        assert.equal(parentAcc.children, 0)
+        assert.ok(childAcc == null)
     });
 
     it("Delete!", async () => {
@@ -74,6 +312,7 @@ describe("vvtec-onchain", () => {
             .delete()
             .accounts({
                 oracle,
+                recipient: provider.wallet.publicKey,
             })
             .rpc();
         console.log("Your transaction signature", tx);
@@ -81,4 +320,1071 @@ describe("vvtec-onchain", () => {
         let oracleAcc = await program.account.oracle.fetchNullable(oracle)
         assert.ok(oracleAcc == null)
     });
+
+    it("Aggregates publisher submissions!", async () => {
+        const feedName = Buffer.alloc(32);
+        feedName.fill("agg");
+
+        const [oracle] = await PublicKey.findProgramAddress(
+            [feedName],
+            program.programId
+        );
+        const [aggregator] = await PublicKey.findProgramAddress(
+            [Buffer.from("aggregator"), oracle.toBuffer()],
+            program.programId
+        );
+        await program.methods
+            .create({
+                name: feedName.toString(),
+                owner: provider.wallet.publicKey,
+                value: null,
+                exponent: 0,
+                policy: { heartbeatSecs: 0, deviationBps: 0 },
+                historyCapacity: 0,
+            })
+            .accounts({
+                oracle,
+                ...configAccounts,
+            })
+            .rpc();
+        await program.methods
+            .initAggregator([provider.wallet.publicKey], 1, new BN(100))
+            .accounts({
+                oracle,
+                aggregator,
+            })
+            .rpc();
+        await program.methods
+            .submit(new BN(5))
+            .accounts({
+                oracle,
+                aggregator,
+                config: configAccounts.config,
+            })
+            .rpc();
+
+        let oracleAcc = await program.account.oracle.fetch(oracle)
+        let aggregatorAcc = await program.account.aggregator.fetch(aggregator)
+
+        assert.ok(oracleAcc.value.eq(new BN(5)))
+        assert.ok(aggregatorAcc.submissions[0].value.eq(new BN(5)))
+
+        // The owner cannot bypass the publishers.
+        await assert.rejects(
+            program.methods
+                .update(new BN(6), null)
+                .accounts({
+                    oracle,
+                    ...configAccounts,
+                })
+                .rpc()
+        );
+
+        await program.methods
+            .closeAggregator()
+            .accounts({
+                oracle,
+                aggregator,
+            })
+            .rpc();
+        await program.methods
+            .delete()
+            .accounts({
+                oracle,
+                recipient: provider.wallet.publicKey,
+            })
+            .rpc();
+    });
+
+    it("Batch update!", async () => {
+        const names = ["batch-a", "batch-b"].map((name) => {
+            const feedName = Buffer.alloc(32);
+            feedName.fill(name);
+            return feedName;
+        });
+        const oracles = [];
+        for (const feedName of names) {
+            const [oracle] = await PublicKey.findProgramAddress(
+                [feedName],
+                program.programId
+            );
+            await program.methods
+                .create({
+                    name: feedName.toString(),
+                    owner: provider.wallet.publicKey,
+                    value: null,
+                    exponent: 0,
+                    policy: { heartbeatSecs: 0, deviationBps: 0 },
+                    historyCapacity: 0,
+                })
+                .accounts({
+                    oracle,
+                    ...configAccounts,
+                })
+                .rpc();
+            oracles.push(oracle);
+        }
+
+        await program.methods
+            .batchUpdate([
+                { value: new BN(10), confidence: null },
+                { value: new BN(-20), confidence: new BN(1) },
+            ])
+            .accounts({
+                ...configAccounts,
+            })
+            .remainingAccounts(oracles.map((pubkey) => ({
+                pubkey,
+                isWritable: true,
+                isSigner: false,
+            })))
+            .rpc();
+
+        const [a, b] = await program.account.oracle.fetchMultiple(oracles);
+        assert.ok(a.value.eq(new BN(10)));
+        assert.ok(b.value.eq(new BN(-20)));
+        assert.ok(b.confidence.eq(new BN(1)));
+        assert.ok(a.updatedAt.eq(b.updatedAt));
+
+        // every update needs its oracle
+        await assert.rejects(
+            program.methods
+                .batchUpdate([
+                    { value: new BN(1), confidence: null },
+                    { value: new BN(2), confidence: null },
+                ])
+                .accounts({
+                    ...configAccounts,
+                })
+                .remainingAccounts([{
+                    pubkey: oracles[0],
+                    isWritable: true,
+                    isSigner: false,
+                }])
+                .rpc()
+        );
+
+        for (const oracle of oracles) {
+            await program.methods
+                .delete()
+                .accounts({
+                    oracle,
+                    recipient: provider.wallet.publicKey,
+                })
+                .rpc();
+        }
+    });
+
+    it("Derives a feed!", async () => {
+        const create = async (name: string, exponent: number) => {
+            const feedName = Buffer.alloc(32);
+            feedName.fill(name);
+            const [oracle] = await PublicKey.findProgramAddress(
+                [feedName],
+                program.programId
+            );
+            await program.methods
+                .create({
+                    name: feedName.toString(),
+                    owner: provider.wallet.publicKey,
+                    value: null,
+                    exponent,
+                    policy: { heartbeatSecs: 0, deviationBps: 0 },
+                    historyCapacity: 0,
+                })
+                .accounts({
+                    oracle,
+                    ...configAccounts,
+                })
+                .rpc();
+            return oracle;
+        };
+        const update = (oracle: PublicKey, value: number) =>
+            program.methods
+                .update(new BN(value), null)
+                .accounts({
+                    oracle,
+                    ...configAccounts,
+                })
+                .rpc();
+
+        const base = await create("derived-base", 0);
+        const quote = await create("derived-quote", 0);
+        const oracle = await create("derived", -2);
+        const [derivation] = await PublicKey.findProgramAddress(
+            [Buffer.from("derivation"), oracle.toBuffer()],
+            program.programId
+        );
+        await update(base, 300);
+        await update(quote, 150);
+
+        await program.methods
+            .initDerivation({
+                ratio: { numerator: base, denominator: quote },
+            })
+            .accounts({
+                oracle,
+                derivation,
+            })
+            .rpc();
+        const sources = [base, quote].map((pubkey) => ({
+            pubkey,
+            isWritable: false,
+            isSigner: false,
+        }));
+        const refresh = () =>
+            program.methods
+                .refresh()
+                .accounts({
+                    oracle,
+                    derivation,
+                    config: configAccounts.config,
+                })
+                .remainingAccounts(sources)
+                .rpc();
+
+        await refresh();
+        let oracleAcc = await program.account.oracle.fetch(oracle);
+        assert.ok(oracleAcc.value.eq(new BN(200)));
+
+        // the owner no longer writes the value
+        await assert.rejects(update(oracle, 1), /AggregatedOracle/);
+
+        await update(quote, 100);
+        await refresh();
+        oracleAcc = await program.account.oracle.fetch(oracle);
+        assert.ok(oracleAcc.value.eq(new BN(300)));
+
+        await program.methods
+            .closeDerivation()
+            .accounts({
+                oracle,
+                derivation,
+            })
+            .rpc();
+        for (const feed of [base, quote, oracle]) {
+            await program.methods
+                .delete()
+                .accounts({
+                    oracle: feed,
+                    recipient: provider.wallet.publicKey,
+                })
+                .rpc();
+        }
+    });
+
+    it("Describes a feed!", async () => {
+        const feedName = Buffer.alloc(32);
+        feedName.fill("described");
+
+        const [oracle] = await PublicKey.findProgramAddress(
+            [feedName],
+            program.programId
+        );
+        const [metadata] = await PublicKey.findProgramAddress(
+            [Buffer.from("metadata"), oracle.toBuffer()],
+            program.programId
+        );
+        await program.methods
+            .create({
+                name: feedName.toString(),
+                owner: provider.wallet.publicKey,
+                value: null,
+                exponent: -2,
+                policy: { heartbeatSecs: 0, deviationBps: 0 },
+                historyCapacity: 0,
+            })
+            .accounts({
+                oracle,
+                ...configAccounts,
+            })
+            .rpc();
+
+        const info = {
+            description: "SOL price in USDT",
+            unit: "USDT",
+            decimals: 2,
+            sources: ["binance", "kraken"],
+            tags: ["crypto"],
+            website: "https://vvtec.network",
+        };
+        await program.methods
+            .initMetadata(info)
+            .accounts({
+                oracle,
+                metadata,
+            })
+            .rpc();
+
+        let metadataAcc = await program.account.metadata.fetch(metadata);
+        assert.ok(metadataAcc.oracle.equals(oracle));
+        assert.deepEqual(metadataAcc.info, info);
+
+        await program.methods
+            .setMetadata({ ...info, tags: ["crypto", "solana"] })
+            .accounts({
+                oracle,
+                metadata,
+            })
+            .rpc();
+        metadataAcc = await program.account.metadata.fetch(metadata);
+        assert.deepEqual(metadataAcc.info.tags, ["crypto", "solana"]);
+
+        await assert.rejects(
+            program.methods
+                .setMetadata({ ...info, unit: "u".repeat(17) })
+                .accounts({
+                    oracle,
+                    metadata,
+                })
+                .rpc(),
+            /InvalidMetadata/
+        );
+
+        await program.methods
+            .closeMetadata()
+            .accounts({
+                oracle,
+                metadata,
+            })
+            .rpc();
+        assert.equal(
+            await provider.connection.getAccountInfo(metadata),
+            null
+        );
+
+        await program.methods
+            .delete()
+            .accounts({
+                oracle,
+                recipient: provider.wallet.publicKey,
+            })
+            .rpc();
+    });
+
+    it("Namespaces feeds by owner!", async () => {
+        const owner = Keypair.generate();
+        await provider.connection.confirmTransaction(
+            await provider.connection.requestAirdrop(
+                owner.publicKey,
+                LAMPORTS_PER_SOL
+            )
+        );
+        const feedName = Buffer.alloc(32);
+        feedName.fill("namespaced");
+        const feed = {
+            name: feedName.toString(),
+            owner: owner.publicKey,
+            value: new BN(1),
+            exponent: 0,
+            policy: { heartbeatSecs: 0, deviationBps: 0 },
+            historyCapacity: 0,
+        };
+
+        // the admin can curate the global namespace
+        const [global] = await PublicKey.findProgramAddress(
+            [feedName],
+            program.programId
+        );
+        await program.methods
+            .setCurated(true)
+            .accounts({
+                config: configAccounts.config,
+            })
+            .rpc();
+        await assert.rejects(
+            program.methods
+                .create(feed)
+                .accounts({
+                    payer: owner.publicKey,
+                    oracle: global,
+                    ...configAccounts,
+                })
+                .signers([owner])
+                .rpc(),
+            /CuratedNamespace/
+        );
+        await program.methods
+            .setCurated(false)
+            .accounts({
+                config: configAccounts.config,
+            })
+            .rpc();
+
+        const [oracle] = await PublicKey.findProgramAddress(
+            [Buffer.from("oracle"), owner.publicKey.toBuffer(), feedName],
+            program.programId
+        );
+        await program.methods
+            .createNamespaced(feed)
+            .accounts({
+                payer: owner.publicKey,
+                owner: owner.publicKey,
+                oracle,
+                ...configAccounts,
+            })
+            .signers([owner])
+            .rpc();
+
+        const oracleAcc = await program.account.oracle.fetch(oracle);
+        assert.ok(oracleAcc.namespace.equals(owner.publicKey));
+        assert.ok(oracleAcc.parent.equals(PublicKey.default));
+
+        await program.methods
+            .update(new BN(2), null)
+            .accounts({
+                owner: owner.publicKey,
+                oracle,
+                ...configAccounts,
+            })
+            .signers([owner])
+            .rpc();
+        await program.methods
+            .delete()
+            .accounts({
+                owner: owner.publicKey,
+                oracle,
+                recipient: owner.publicKey,
+            })
+            .signers([owner])
+            .rpc();
+    });
+
+    it("Reports health!", async () => {
+        const feedName = Buffer.alloc(32);
+        feedName.fill("health");
+
+        const [oracle] = await PublicKey.findProgramAddress(
+            [feedName],
+            program.programId
+        );
+        await program.methods
+            .create({
+                name: feedName.toString(),
+                owner: provider.wallet.publicKey,
+                value: new BN(1),
+                exponent: 0,
+                policy: { heartbeatSecs: 1, deviationBps: 50 },
+                historyCapacity: 0,
+            })
+            .accounts({
+                oracle,
+                ...configAccounts,
+            })
+            .rpc();
+
+        const oracleAcc = await program.account.oracle.fetch(oracle)
+        assert.equal(oracleAcc.policy.heartbeatSecs, 1)
+        assert.equal(oracleAcc.policy.deviationBps, 50)
+        assert.equal(
+            await program.methods.checkHealth().accounts({ oracle }).view(),
+            true
+        )
+
+        await new Promise((resolve) => setTimeout(resolve, 3000));
+        assert.equal(
+            await program.methods.checkHealth().accounts({ oracle }).view(),
+            false
+        )
+
+        await program.methods
+            .delete()
+            .accounts({
+                oracle,
+                recipient: provider.wallet.publicKey,
+            })
+            .rpc();
+    });
+
+    it("Relays signed values!", async () => {
+        const feedName = Buffer.alloc(32);
+        feedName.fill("signed");
+        const owner = (provider.wallet as anchor.Wallet).payer;
+        const relayer = Keypair.generate();
+
+        const [oracle] = await PublicKey.findProgramAddress(
+            [feedName],
+            program.programId
+        );
+        await program.methods
+            .create({
+                name: feedName.toString(),
+                owner: owner.publicKey,
+                value: null,
+                exponent: 0,
+                policy: { heartbeatSecs: 0, deviationBps: 0 },
+                historyCapacity: 0,
+            })
+            .accounts({
+                oracle,
+                ...configAccounts,
+            })
+            .rpc();
+        await provider.connection.confirmTransaction(
+            await provider.connection.requestAirdrop(
+                relayer.publicKey,
+                LAMPORTS_PER_SOL
+            )
+        );
+        await new Promise((resolve) => setTimeout(resolve, 2000));
+
+        let oracleAcc = await program.account.oracle.fetch(oracle)
+        const message = {
+            name: [...feedName],
+            parent: PublicKey.default,
+            value: new BN(-42),
+            confidence: null,
+            timestamp: oracleAcc.updatedAt.addn(1),
+        };
+        // PriceMessage::to_bytes
+        const signedBytes = Buffer.concat([
+            Buffer.from("vvtec price message v1"),
+            feedName,
+            PublicKey.default.toBuffer(),
+            Buffer.from([1]),
+            message.value.toTwos(128).toArrayLike(Buffer, "le", 16),
+            Buffer.from([0]),
+            message.timestamp.toTwos(64).toArrayLike(Buffer, "le", 8),
+        ]);
+        const submit = (signer: Keypair) => program.methods
+            .submitSigned(message)
+            .accounts({
+                relayer: relayer.publicKey,
+                oracle,
+                ...configAccounts,
+                instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+            })
+            .preInstructions([
+                Ed25519Program.createInstructionWithPrivateKey({
+                    privateKey: signer.secretKey,
+                    message: signedBytes,
+                }),
+            ])
+            .signers([relayer])
+            .rpc();
+
+        // Only the owner signs values.
+        await assert.rejects(submit(relayer), /OwnerMismatch/);
+
+        await submit(owner);
+        oracleAcc = await program.account.oracle.fetch(oracle)
+        assert.ok(oracleAcc.value.eq(new BN(-42)))
+        assert.ok(oracleAcc.updatedAt.eq(message.timestamp))
+
+        // A signed value cannot be replayed.
+        await assert.rejects(submit(owner), /OutdatedMessage/);
+
+        await program.methods
+            .delete()
+            .accounts({
+                oracle,
+                recipient: provider.wallet.publicKey,
+            })
+            .rpc();
+    });
+
+    it("Requests and fulfills values!", async () => {
+        const feedName = Buffer.alloc(32);
+        feedName.fill("on-demand");
+        const requester = Keypair.generate();
+
+        const [oracle] = await PublicKey.findProgramAddress(
+            [feedName],
+            program.programId
+        );
+        await program.methods
+            .create({
+                name: feedName.toString(),
+                owner: provider.wallet.publicKey,
+                value: null,
+                exponent: 0,
+                policy: { heartbeatSecs: 0, deviationBps: 0 },
+                historyCapacity: 0,
+            })
+            .accounts({
+                oracle,
+                ...configAccounts,
+            })
+            .rpc();
+        await provider.connection.confirmTransaction(
+            await provider.connection.requestAirdrop(
+                requester.publicKey,
+                LAMPORTS_PER_SOL
+            )
+        );
+
+        const configAcc = await program.account.config.fetch(
+            configAccounts.config
+        );
+        const id = configAcc.nextRequestId;
+        const [request] = await PublicKey.findProgramAddress(
+            [Buffer.from("request"), id.toArrayLike(Buffer, "le", 8)],
+            program.programId
+        );
+        await program.methods
+            .openRequest()
+            .accounts({
+                requester: requester.publicKey,
+                oracle,
+                request,
+                ...configAccounts,
+            })
+            .signers([requester])
+            .rpc();
+
+        let requestAcc = await program.account.request.fetch(request)
+        assert.ok(requestAcc.id.eq(id))
+        assert.ok(requestAcc.fulfilledAt == null)
+
+        // Only the owner of the oracle fulfills requests.
+        await assert.rejects(
+            program.methods
+                .fulfillRequest(new BN(3), null)
+                .accounts({
+                    owner: requester.publicKey,
+                    oracle,
+                    request,
+                    config: configAccounts.config,
+                })
+                .signers([requester])
+                .rpc()
+        );
+
+        await program.methods
+            .fulfillRequest(new BN(3), null)
+            .accounts({
+                oracle,
+                request,
+                config: configAccounts.config,
+            })
+            .rpc();
+
+        requestAcc = await program.account.request.fetch(request)
+        const oracleAcc = await program.account.oracle.fetch(oracle)
+        assert.ok(requestAcc.value.eq(new BN(3)))
+        assert.ok(requestAcc.fulfilledAt != null)
+        assert.ok(requestAcc.roundId.eq(oracleAcc.roundId))
+
+        await assert.rejects(
+            program.methods
+                .fulfillRequest(new BN(4), null)
+                .accounts({
+                    oracle,
+                    request,
+                    config: configAccounts.config,
+                })
+                .rpc(),
+            /RequestFulfilled/
+        );
+
+        await program.methods
+            .closeRequest()
+            .accounts({
+                requester: requester.publicKey,
+                request,
+            })
+            .signers([requester])
+            .rpc();
+        await program.methods
+            .delete()
+            .accounts({
+                oracle,
+                recipient: provider.wallet.publicKey,
+            })
+            .rpc();
+    });
+
+    it("Pause and fees!", async () => {
+        const feedName = Buffer.alloc(32);
+        feedName.fill("fees");
+        const feed = {
+            name: feedName.toString(),
+            owner: provider.wallet.publicKey,
+            value: new BN(1),
+            exponent: 0,
+            policy: { heartbeatSecs: 0, deviationBps: 0 },
+            historyCapacity: 0,
+        };
+
+        const [oracle] = await PublicKey.findProgramAddress(
+            [feedName],
+            program.programId
+        );
+
+        await program.methods
+            .setPaused(true)
+            .accounts({
+                config: configAccounts.config,
+            })
+            .rpc();
+        await assert.rejects(
+            program.methods
+                .create(feed)
+                .accounts({
+                    oracle,
+                    ...configAccounts,
+                })
+                .rpc(),
+            /Paused/
+        );
+        await program.methods
+            .setPaused(false)
+            .accounts({
+                config: configAccounts.config,
+            })
+            .rpc();
+
+        // large enough to leave the new treasury account rent exempt
+        const fee = new BN(LAMPORTS_PER_SOL / 100);
+        const treasury = Keypair.generate().publicKey;
+        await program.methods
+            .setFees(treasury, fee, fee, fee)
+            .accounts({
+                config: configAccounts.config,
+            })
+            .rpc();
+        await program.methods
+            .create(feed)
+            .accounts({
+                oracle,
+                config: configAccounts.config,
+                treasury,
+            })
+            .rpc();
+        assert.equal(
+            await provider.connection.getBalance(treasury),
+            fee.toNumber()
+        );
+
+        // restore the defaults for other suites
+        await program.methods
+            .setFees(configAccounts.treasury, new BN(0), new BN(0), new BN(0))
+            .accounts({
+                config: configAccounts.config,
+            })
+            .rpc();
+        await program.methods
+            .delete()
+            .accounts({
+                oracle,
+                recipient: provider.wallet.publicKey,
+            })
+            .rpc();
+    });
+
+    it("Names a feed longer than a seed!", async () => {
+        const name = "equities-nasdaq-aapl-close-adjusted";
+        const nameSeed = createHash("sha256").update(name).digest();
+        const [oracle] = await PublicKey.findProgramAddress(
+            [nameSeed],
+            program.programId
+        );
+        await program.methods
+            .create({
+                name,
+                owner: provider.wallet.publicKey,
+                value: new BN(17),
+                exponent: 0,
+                policy: { heartbeatSecs: 0, deviationBps: 0 },
+                historyCapacity: 0,
+            })
+            .accounts({
+                oracle,
+                ...configAccounts,
+            })
+            .rpc();
+
+        let oracleAcc = await program.account.oracle.fetch(oracle)
+        assert.equal(oracleAcc.name, name)
+        assert.ok(nameSeed.equals(Buffer.from(oracleAcc.nameSeed)))
+
+        await assert.rejects(
+            program.methods
+                .create({
+                    name: "x".repeat(129),
+                    owner: provider.wallet.publicKey,
+                    value: null,
+                    exponent: 0,
+                    policy: { heartbeatSecs: 0, deviationBps: 0 },
+                    historyCapacity: 0,
+                })
+                .accounts({
+                    oracle: (await PublicKey.findProgramAddress(
+                        [createHash("sha256").update("x".repeat(129)).digest()],
+                        program.programId
+                    ))[0],
+                    ...configAccounts,
+                })
+                .rpc()
+        );
+
+        // dots separate the segments of feed paths
+        const dotted = "crypto.sol";
+        await assert.rejects(
+            program.methods
+                .create({
+                    name: dotted,
+                    owner: provider.wallet.publicKey,
+                    value: null,
+                    exponent: 0,
+                    policy: { heartbeatSecs: 0, deviationBps: 0 },
+                    historyCapacity: 0,
+                })
+                .accounts({
+                    oracle: (await PublicKey.findProgramAddress(
+                        [Buffer.concat([Buffer.from(dotted), Buffer.alloc(22)])],
+                        program.programId
+                    ))[0],
+                    ...configAccounts,
+                })
+                .rpc()
+        );
+
+        await program.methods
+            .delete()
+            .accounts({
+                oracle,
+                recipient: provider.wallet.publicKey,
+            })
+            .rpc();
+    });
+
+    it("Updates a zero-copy feed!", async () => {
+        const name = "fast-feed";
+        const nameSeed = Buffer.alloc(32);
+        nameSeed.write(name);
+        const owner = provider.wallet.publicKey;
+        const [oracle] = await PublicKey.findProgramAddress(
+            [Buffer.from("fast"), owner.toBuffer(), nameSeed],
+            program.programId
+        );
+        await program.methods
+            .createFast(name, -2)
+            .accounts({
+                owner,
+                oracle,
+                ...configAccounts,
+            })
+            .rpc();
+
+        await program.methods
+            .updateFast(new BN(12345), new BN(7))
+            .accounts({
+                oracle,
+                ...configAccounts,
+            })
+            .rpc();
+
+        // the account is not borsh encoded, read the fields at their offsets
+        const { data } = await provider.connection.getAccountInfo(oracle);
+        assert.equal(data.length, 8 + 144);
+        assert.ok(owner.toBuffer().equals(data.subarray(8, 40)));
+        assert.ok(nameSeed.equals(data.subarray(40, 72)));
+        assert.ok(new BN(data.subarray(72, 88), "le").eqn(12345));
+        assert.ok(new BN(data.subarray(88, 104), "le").eqn(7));
+        assert.equal(new BN(data.subarray(136, 144), "le").toNumber(), 1);
+        assert.equal(data[144], 3);
+        assert.equal(data.readInt8(145), -2);
+
+        const stranger = Keypair.generate();
+        await assert.rejects(
+            program.methods
+                .updateFast(new BN(1), null)
+                .accounts({
+                    owner: stranger.publicKey,
+                    oracle,
+                    ...configAccounts,
+                })
+                .signers([stranger])
+                .rpc()
+        );
+
+        await program.methods
+            .deleteFast()
+            .accounts({
+                oracle,
+            })
+            .rpc();
+        assert.equal(await provider.connection.getAccountInfo(oracle), null);
+    });
+
+    it("Refunds rent to a recipient!", async () => {
+        const feedName = Buffer.alloc(32);
+        feedName.fill("refund");
+        const [oracle] = await PublicKey.findProgramAddress(
+            [feedName],
+            program.programId
+        );
+        await program.methods
+            .create({
+                name: feedName.toString(),
+                owner: provider.wallet.publicKey,
+                value: new BN(1),
+                exponent: 0,
+                policy: { heartbeatSecs: 0, deviationBps: 0 },
+                historyCapacity: 0,
+            })
+            .accounts({
+                oracle,
+                ...configAccounts,
+            })
+            .rpc();
+
+        const rent = await provider.connection.getBalance(oracle);
+        const recipient = Keypair.generate().publicKey;
+        await program.methods
+            .delete()
+            .accounts({
+                oracle,
+                recipient,
+            })
+            .rpc();
+        assert.equal(await provider.connection.getBalance(recipient), rent);
+    });
+
+    it("Reaps abandoned feeds!", async () => {
+        const feedName = Buffer.alloc(32);
+        feedName.fill("abandoned");
+        const [oracle] = await PublicKey.findProgramAddress(
+            [feedName],
+            program.programId
+        );
+        await program.methods
+            .create({
+                name: feedName.toString(),
+                owner: provider.wallet.publicKey,
+                value: new BN(1),
+                exponent: 0,
+                policy: { heartbeatSecs: 0, deviationBps: 0 },
+                historyCapacity: 0,
+            })
+            .accounts({
+                oracle,
+                ...configAccounts,
+            })
+            .rpc();
+        const childName = Buffer.alloc(32);
+        childName.fill("abandoned-child");
+        const [child] = await PublicKey.findProgramAddress(
+            [oracle.toBuffer(), childName],
+            program.programId
+        );
+        await program.methods
+            .attachChild({
+                name: childName.toString(),
+                owner: provider.wallet.publicKey,
+                value: new BN(1),
+                exponent: 0,
+                policy: { heartbeatSecs: 0, deviationBps: 0 },
+                historyCapacity: 0,
+            })
+            .accounts({
+                parent: oracle,
+                child,
+                ...configAccounts,
+            })
+            .rpc();
+        const metadataOf = async (feed: PublicKey) =>
+            (
+                await PublicKey.findProgramAddress(
+                    [Buffer.from("metadata"), feed.toBuffer()],
+                    program.programId
+                )
+            )[0];
+        const metadata = await metadataOf(child);
+        await program.methods
+            .initMetadata({
+                description: "",
+                unit: "",
+                decimals: 0,
+                sources: [],
+                tags: [],
+                website: "",
+            })
+            .accounts({
+                oracle: child,
+                metadata,
+            })
+            .rpc();
+
+        const treasury = Keypair.generate().publicKey;
+        await program.methods
+            .setFees(treasury, new BN(0), new BN(0), new BN(0))
+            .accounts({
+                config: configAccounts.config,
+            })
+            .rpc();
+        const reap = async (
+            feed: PublicKey,
+            parent: PublicKey | null,
+            admin?: Keypair
+        ) =>
+            program.methods
+                .reap()
+                .accounts({
+                    admin: admin ? admin.publicKey : provider.wallet.publicKey,
+                    oracle: feed,
+                    parent,
+                    metadata: await metadataOf(feed),
+                    aggregator: null,
+                    derivation: null,
+                    config: configAccounts.config,
+                    treasury,
+                })
+                .signers(admin ? [admin] : [])
+                .rpc();
+
+        // reaping is disabled until the admin sets a period
+        await assert.rejects(reap(child, oracle), /OracleNotAbandoned/);
+        await assert.rejects(
+            program.methods
+                .setReapAfter(new BN(-1))
+                .accounts({
+                    config: configAccounts.config,
+                })
+                .rpc()
+        );
+        await program.methods
+            .setReapAfter(new BN(1))
+            .accounts({
+                config: configAccounts.config,
+            })
+            .rpc();
+        await new Promise((resolve) => setTimeout(resolve, 2000));
+
+        await assert.rejects(
+            reap(child, oracle, Keypair.generate()),
+            /OwnerMismatch/
+        );
+        await assert.rejects(reap(oracle, null), /OracleHasChildren/);
+        await assert.rejects(reap(child, null), /ParentMismatch/);
+
+        // children are reaped first, detaching them from their parent
+        const rent =
+            (await provider.connection.getBalance(oracle)) +
+            (await provider.connection.getBalance(child)) +
+            (await provider.connection.getBalance(metadata));
+        await reap(child, oracle);
+        assert.equal(await provider.connection.getAccountInfo(child), null);
+        assert.equal(await provider.connection.getAccountInfo(metadata), null);
+        const parentAcc = await program.account.oracle.fetch(oracle);
+        assert.equal(parentAcc.children, 0);
+        await reap(oracle, null);
+        assert.equal(await provider.connection.getAccountInfo(oracle), null);
+        assert.equal(await provider.connection.getBalance(treasury), rent);
+
+        // restore the defaults for other suites
+        await program.methods
+            .setReapAfter(new BN(0))
+            .accounts({
+                config: configAccounts.config,
+            })
+            .rpc();
+        await program.methods
+            .setFees(configAccounts.treasury, new BN(0), new BN(0), new BN(0))
+            .accounts({
+                config: configAccounts.config,
+            })
+            .rpc();
+    });
 });
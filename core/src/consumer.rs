@@ -0,0 +1,97 @@
+//! Helpers for programs that read vvtec feeds on-chain.
+//!
+//! Pass the oracle account to your instruction as an unchecked account and
+//! load it through one of the functions below, they verify that the account
+//! is a genuine vvtec oracle before handing out its contents.
+
+use anchor_lang::prelude::*;
+
+use crate::{state::Oracle, Error, Result};
+
+/// Loads an oracle account owned by the vvtec program.
+///
+/// Verifies the owner of the account, its discriminator and that its address
+/// is the PDA derived from the parent and name stored in it. Accounts that
+/// still use an older layout are converted to the current one.
+pub fn load(oracle_account: &AccountInfo) -> Result<Oracle> {
+  if oracle_account.owner != &crate::id() {
+    return Err(Error::OwnerMismatch);
+  }
+
+  let data = oracle_account
+    .try_borrow_data()
+    .map_err(|_| Error::ProgramError)?;
+  let oracle = Oracle::from_account_data(&data)
+    .map_err(|_| Error::UninitializedOracle)?;
+
+  let (expected, _) = Pubkey::find_program_address(
+    &[oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+    &crate::id(),
+  );
+  if &expected != oracle_account.key {
+    return Err(Error::InvalidOracle);
+  }
+
+  Ok(oracle)
+}
+
+/// Loads an oracle that has a value written within the last `max_age_secs`
+/// seconds.
+pub fn load_fresh(
+  oracle_account: &AccountInfo,
+  max_age_secs: i64,
+) -> Result<Oracle> {
+  let oracle = load(oracle_account)?;
+  if oracle.value.is_none() {
+    return Err(Error::EmptyOracle);
+  }
+
+  let now = Clock::get()
+    .map_err(|_| Error::ProgramError)?
+    .unix_timestamp;
+  if now.saturating_sub(oracle.updated_at) > max_age_secs {
+    return Err(Error::StaleOracle);
+  }
+
+  Ok(oracle)
+}
+
+/// Loads an oracle that is healthy under its own update policy, see
+/// [`Oracle::is_healthy`].
+pub fn load_healthy(oracle_account: &AccountInfo) -> Result<Oracle> {
+  let oracle = load(oracle_account)?;
+  if oracle.value.is_none() {
+    return Err(Error::EmptyOracle);
+  }
+
+  let now = Clock::get()
+    .map_err(|_| Error::ProgramError)?
+    .unix_timestamp;
+  if !oracle.is_healthy(now) {
+    return Err(Error::StaleOracle);
+  }
+
+  Ok(oracle)
+}
+
+/// Like [`load_fresh`], but additionally requires the confidence interval
+/// to be at most `max_confidence_bps` basis points of the value.
+///
+/// Oracles that do not publish a confidence are treated as exact.
+pub fn load_confident(
+  oracle_account: &AccountInfo,
+  max_age_secs: i64,
+  max_confidence_bps: u16,
+) -> Result<Oracle> {
+  let oracle = load_fresh(oracle_account, max_age_secs)?;
+  let value = oracle.value.ok_or(Error::EmptyOracle)?.unsigned_abs();
+
+  if let Some(confidence) = oracle.confidence {
+    let limit = value.saturating_mul(max_confidence_bps as u128) / 10_000;
+    if confidence > limit {
+      return Err(Error::ConfidenceTooWide);
+    }
+  }
+
+  Ok(oracle)
+}
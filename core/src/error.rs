@@ -44,4 +44,52 @@ pub enum Error {
 
   #[msg("Solana program error")]
   ProgramError,
+
+  #[msg("Oracle still has child feeds attached")]
+  OracleHasChildren,
+
+  #[msg("Oracle is not a child of the given parent")]
+  ParentMismatch,
+
+  #[msg("Oracle value is aggregated or derived from other sources")]
+  AggregatedOracle,
+
+  #[msg("Signer is not an authorized publisher of this oracle")]
+  UnauthorizedPublisher,
+
+  #[msg("Quorum must be between one and the number of publishers")]
+  InvalidQuorum,
+
+  #[msg("Oracle has no value")]
+  EmptyOracle,
+
+  #[msg("Oracle value is older than the allowed age")]
+  StaleOracle,
+
+  #[msg("Oracle confidence interval is wider than allowed")]
+  ConfidenceTooWide,
+
+  #[msg("Program is paused")]
+  Paused,
+
+  #[msg("Request was already fulfilled")]
+  RequestFulfilled,
+
+  #[msg("Signed value is not newer than the current value")]
+  OutdatedMessage,
+
+  #[msg("Derivation sources do not match its expression")]
+  InvalidDerivation,
+
+  #[msg("Metadata field exceeds its size limit")]
+  InvalidMetadata,
+
+  #[msg("Only the admin can create feeds in the global namespace")]
+  CuratedNamespace,
+
+  #[msg("Oracle was written too recently to be reaped")]
+  OracleNotAbandoned,
+
+  #[msg("Arithmetic overflow")]
+  Overflow,
 }
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+/// Emitted when a feed is created, either as a root feed or as a child of
+/// another feed.
+#[event]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OracleCreated {
+    pub oracle: Pubkey,
+    pub owner: Pubkey,
+    pub name: String,
+    pub value: Option<i128>,
+    pub timestamp: i64,
+}
+
+/// Emitted whenever the value of a feed is written.
+#[event]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OracleUpdated {
+    pub oracle: Pubkey,
+    pub old_value: Option<i128>,
+    pub new_value: Option<i128>,
+    pub old_timestamp: i64,
+    pub new_timestamp: i64,
+    pub slot: u64,
+    pub round_id: u64,
+}
+
+/// Emitted when a feed is deleted or detached from its parent.
+#[event]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OracleDeleted {
+    pub oracle: Pubkey,
+    pub owner: Pubkey,
+}
+
+/// Emitted when a consumer requests a fresh value of a feed. The owner of the
+/// feed is expected to fulfill the request.
+#[event]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestOpened {
+    pub request: Pubkey,
+    pub id: u64,
+    pub oracle: Pubkey,
+    pub requester: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted when the owner of a feed fulfills a request.
+#[event]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestFulfilled {
+    pub request: Pubkey,
+    pub id: u64,
+    pub oracle: Pubkey,
+    pub value: Option<i128>,
+    pub round_id: u64,
+}
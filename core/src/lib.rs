@@ -5,11 +5,24 @@ pub use error::Error;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// The value of a feed, scaled by the exponent of the feed.
+pub type FeedValue = i128;
+
+pub mod events;
+pub mod message;
+pub mod native;
 pub mod state;
 
-use anchor_lang::prelude::*;
+#[cfg(feature = "cpi")]
+pub mod consumer;
+
+use anchor_lang::{prelude::*, solana_program::sysvar};
 
-use state::Oracle;
+use message::PriceMessage;
+use state::{
+    name_seed, Aggregator, Config, Derivation, Expression, FastOracle,
+    FeedMetadata, FeedMode, Metadata, Oracle, Request, UpdatePolicy,
+};
 
 declare_id!("vvtecC41zqsHouFA6EqSdcyJL9MdL9sk8E3pZWNQzyAY");
 
@@ -18,40 +31,368 @@ declare_id!("vvtecC41zqsHouFA6EqSdcyJL9MdL9sk8E3pZWNQzyAY");
 pub mod vvtec_onchain {
     use super::*;
 
+    pub fn init_config(
+        ctx: Context<InitConfig>,
+        treasury: Pubkey,
+        create_fee: u64,
+        update_fee: u64,
+        request_fee: u64,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn set_admin(ctx: Context<Configure>, admin: Pubkey) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn set_paused(ctx: Context<Configure>, paused: bool) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn set_fees(
+        ctx: Context<Configure>,
+        treasury: Pubkey,
+        create_fee: u64,
+        update_fee: u64,
+        request_fee: u64,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn set_reap_after(
+        ctx: Context<Configure>,
+        reap_after_secs: i64,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn set_curated(ctx: Context<Configure>, curated: bool) -> Result<()> {
+        Ok(())
+    }
+
     pub fn create(ctx: Context<Create>, feed: Feed) -> Result<()> {
         Ok(())
     }
 
-    pub fn update(ctx: Context<Update>, value: Option<u128>) -> Result<()> {
+    pub fn create_namespaced(
+        ctx: Context<CreateNamespaced>,
+        feed: Feed,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn attach_child(ctx: Context<AttachChild>, feed: Feed) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn update(
+        ctx: Context<Update>,
+        value: Option<i128>,
+        confidence: Option<u128>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn batch_update<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchUpdate<'info>>,
+        updates: Vec<FeedUpdate>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn submit_signed(
+        ctx: Context<SubmitSigned>,
+        message: PriceMessage,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn open_request(ctx: Context<OpenRequest>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn fulfill_request(
+        ctx: Context<FulfillRequest>,
+        value: Option<i128>,
+        confidence: Option<u128>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn close_request(_ctx: Context<CloseRequest>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn check_health(ctx: Context<CheckHealth>) -> Result<bool> {
+        Ok(false)
+    }
+
+    pub fn detach_child(ctx: Context<DetachChild>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn propose_owner(
+        ctx: Context<ProposeOwner>,
+        new_owner: Option<Pubkey>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn accept_owner(ctx: Context<AcceptOwner>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn init_aggregator(
+        ctx: Context<InitAggregator>,
+        publishers: Vec<Pubkey>,
+        quorum: u8,
+        max_age_slots: u64,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn submit(ctx: Context<Submit>, value: i128) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn close_aggregator(ctx: Context<CloseAggregator>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn init_derivation(
+        ctx: Context<InitDerivation>,
+        expression: Expression,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn refresh<'info>(
+        ctx: Context<'_, '_, '_, 'info, Refresh<'info>>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn close_derivation(ctx: Context<CloseDerivation>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn init_metadata(
+        ctx: Context<InitMetadata>,
+        info: FeedMetadata,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn set_metadata(
+        ctx: Context<SetMetadata>,
+        info: FeedMetadata,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn close_metadata(_ctx: Context<CloseMetadata>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn migrate(ctx: Context<Migrate>) -> Result<()> {
         Ok(())
     }
 
     pub fn delete(_ctx: Context<Delete>) -> Result<()> {
         Ok(())
     }
+
+    pub fn reap(ctx: Context<Reap>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn create_fast(
+        ctx: Context<CreateFast>,
+        name: String,
+        exponent: i8,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn update_fast(
+        ctx: Context<UpdateFast>,
+        value: Option<i128>,
+        confidence: Option<u128>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn delete_fast(_ctx: Context<DeleteFast>) -> Result<()> {
+        Ok(())
+    }
 }
 
 #[derive(Accounts)]
-#[instruction(feed: Oracle)]
-pub struct Create<'info> {
+pub struct InitConfig<'info> {
     #[account(mut)]
+    authority: Signer<'info>,
+    #[account(
+        init,
+        payer = authority,
+        space = Config::SPACE,
+        seeds = [Config::SEED],
+        bump
+    )]
+    config: Account<'info, Config>,
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key())
+    )]
+    program: Program<'info, crate::program::VvtecOnchain>,
+    #[account(
+        constraint = program_data.upgrade_authority_address
+            == Some(authority.key()) @ Error::OwnerMismatch
+    )]
+    program_data: Account<'info, ProgramData>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Configure<'info> {
+    admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [Config::SEED],
+        bump,
+        has_one = admin @ Error::OwnerMismatch,
+    )]
+    config: Account<'info, Config>,
+}
+
+/// Creates a root feed in the global namespace, only the admin can create
+/// them while it is [`Config::curated`].
+#[derive(Accounts)]
+#[instruction(feed: Feed)]
+pub struct Create<'info> {
+    #[account(
+        mut,
+        constraint = !config.curated || payer.key() == config.admin
+            @ Error::CuratedNamespace,
+    )]
     payer: Signer<'info>,
     #[account(
         init, 
         payer = payer, 
-        space = 8 + 89, 
-        seeds = [&feed.name],
+        space = Oracle::space(feed.name.len(), feed.history_capacity),
+        seeds = [&name_seed(&feed.name)],
         bump
     )]
     oracle: Account<'info, Oracle>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+    /// CHECK: only receives the fee, must match the configured treasury.
+    #[account(mut, address = config.treasury)]
+    treasury: UncheckedAccount<'info>,
     pub system_program: Program<'info, System>,
 }
 
+/// Creates a root feed in the namespace of its owner, who must sign.
+#[derive(Accounts)]
+#[instruction(feed: Feed)]
+pub struct CreateNamespaced<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+    #[account(address = feed.owner @ Error::OwnerMismatch)]
+    owner: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = Oracle::space(feed.name.len(), feed.history_capacity),
+        seeds = [
+            Oracle::SEED,
+            feed.owner.as_ref(),
+            &name_seed(&feed.name),
+        ],
+        bump
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+    /// CHECK: only receives the fee, must match the configured treasury.
+    #[account(mut, address = config.treasury)]
+    treasury: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+}
+
 #[derive(Debug, AnchorSerialize, AnchorDeserialize)]
 pub struct Feed {
     pub owner: Pubkey,
-    pub name: [u8; 32],
-    pub value: Option<u128>,
+    pub name: String,
+    pub value: Option<i128>,
+    pub exponent: i8,
+    pub policy: UpdatePolicy,
+    pub history_capacity: u16,
+}
+
+#[derive(Accounts)]
+#[instruction(feed: Feed)]
+pub struct AttachChild<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [parent.prefix_seed(), parent.scope_seed(), &parent.name_seed],
+        bump,
+        has_one = owner,
+    )]
+    parent: Account<'info, Oracle>,
+    #[account(
+        init,
+        payer = payer,
+        space = Oracle::space(feed.name.len(), feed.history_capacity),
+        seeds = [parent.key().as_ref(), &name_seed(&feed.name)],
+        bump
+    )]
+    child: Account<'info, Oracle>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+    /// CHECK: only receives the fee, must match the configured treasury.
+    #[account(mut, address = config.treasury)]
+    treasury: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DetachChild<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [parent.prefix_seed(), parent.scope_seed(), &parent.name_seed],
+        bump,
+        has_one = owner,
+    )]
+    parent: Account<'info, Oracle>,
+    #[account(
+        mut,
+        seeds = [parent.key().as_ref(), &child.name_seed],
+        bump,
+        constraint = child.parent == parent.key() @ Error::ParentMismatch,
+        constraint = child.children == 0 @ Error::OracleHasChildren,
+        constraint = child.mode == FeedMode::Direct @ Error::AggregatedOracle,
+        close = child_owner,
+    )]
+    child: Account<'info, Oracle>,
+    /// CHECK: only receives the rent of the closed child, must be its owner.
+    #[account(mut, address = child.owner @ Error::OwnerMismatch)]
+    child_owner: UncheckedAccount<'info>,
 }
 
 #[derive(Accounts)]
@@ -60,23 +401,515 @@ pub struct Update<'info> {
     owner: Signer<'info>,
     #[account(
         mut,
-        seeds = [&oracle.name],
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
         bump,
         has_one = owner,
+        constraint = oracle.mode == FeedMode::Direct @ Error::AggregatedOracle,
     )]
     oracle: Account<'info, Oracle>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+    /// CHECK: only receives the fee, must match the configured treasury.
+    #[account(mut, address = config.treasury)]
+    treasury: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
 }
 
+/// A new value for one of the oracles passed to `batch_update`.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct FeedUpdate {
+    pub value: Option<i128>,
+    pub confidence: Option<u128>,
+}
+
+/// Writes many oracles of the same owner at once. The oracles are passed as
+/// writable remaining accounts, in the same order as the updates.
 #[derive(Accounts)]
-pub struct Delete<'info> {
+pub struct BatchUpdate<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+    /// CHECK: only receives the fee, must match the configured treasury.
+    #[account(mut, address = config.treasury)]
+    treasury: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CheckHealth<'info> {
+    #[account(
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump
+    )]
+    oracle: Account<'info, Oracle>,
+}
+
+#[derive(Accounts)]
+pub struct OpenRequest<'info> {
+    #[account(mut)]
+    requester: Signer<'info>,
+    #[account(
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        init,
+        payer = requester,
+        space = Request::SPACE,
+        seeds = [Request::SEED, config.next_request_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    request: Account<'info, Request>,
+    #[account(
+        mut,
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+    /// CHECK: only receives the fee, must match the configured treasury.
+    #[account(mut, address = config.treasury)]
+    treasury: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FulfillRequest<'info> {
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        has_one = owner,
+        constraint = oracle.mode == FeedMode::Direct @ Error::AggregatedOracle,
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        mut,
+        seeds = [Request::SEED, request.id.to_le_bytes().as_ref()],
+        bump,
+        has_one = oracle,
+        constraint = !request.is_fulfilled() @ Error::RequestFulfilled,
+    )]
+    request: Account<'info, Request>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRequest<'info> {
     #[account(mut)]
+    requester: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [Request::SEED, request.id.to_le_bytes().as_ref()],
+        bump,
+        has_one = requester,
+        close = requester,
+    )]
+    request: Account<'info, Request>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitSigned<'info> {
+    #[account(mut)]
+    relayer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        constraint = oracle.mode == FeedMode::Direct @ Error::AggregatedOracle,
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+    /// CHECK: only receives the fee, must match the configured treasury.
+    #[account(mut, address = config.treasury)]
+    treasury: UncheckedAccount<'info>,
+    /// CHECK: the instructions sysvar, checked by its address.
+    #[account(address = sysvar::instructions::ID)]
+    instructions: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeOwner<'info> {
     owner: Signer<'info>,
     #[account(
         mut,
-        seeds = [&oracle.name],
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
         bump,
         has_one = owner,
+    )]
+    oracle: Account<'info, Oracle>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptOwner<'info> {
+    new_owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        constraint = oracle.pending_owner == Some(new_owner.key())
+            @ Error::OwnerMismatch,
+    )]
+    oracle: Account<'info, Oracle>,
+}
+
+#[derive(Accounts)]
+#[instruction(publishers: Vec<Pubkey>)]
+pub struct InitAggregator<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        has_one = owner,
+        constraint = oracle.mode == FeedMode::Direct @ Error::AggregatedOracle,
+        constraint = publishers.len() <= Aggregator::MAX_PUBLISHERS,
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        init,
+        payer = owner,
+        space = Aggregator::space(publishers.len()),
+        seeds = [Aggregator::SEED, oracle.key().as_ref()],
+        bump
+    )]
+    aggregator: Account<'info, Aggregator>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Submit<'info> {
+    publisher: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        constraint = oracle.mode == FeedMode::Aggregate,
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        mut,
+        seeds = [Aggregator::SEED, oracle.key().as_ref()],
+        bump,
+        has_one = oracle,
+    )]
+    aggregator: Account<'info, Aggregator>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct CloseAggregator<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        has_one = owner,
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        mut,
+        seeds = [Aggregator::SEED, oracle.key().as_ref()],
+        bump,
+        has_one = oracle,
         close = owner,
     )]
+    aggregator: Account<'info, Aggregator>,
+}
+
+#[derive(Accounts)]
+#[instruction(expression: Expression)]
+pub struct InitDerivation<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        has_one = owner,
+        constraint = oracle.mode == FeedMode::Direct @ Error::AggregatedOracle,
+    )]
     oracle: Account<'info, Oracle>,
+    #[account(
+        init,
+        payer = owner,
+        space = Derivation::space(expression.sources().len()),
+        seeds = [Derivation::SEED, oracle.key().as_ref()],
+        bump
+    )]
+    derivation: Account<'info, Derivation>,
+    system_program: Program<'info, System>,
+}
+
+/// Recomputes a derived oracle. The sources are passed as remaining
+/// accounts, in the order of [`Expression::sources`].
+#[derive(Accounts)]
+pub struct Refresh<'info> {
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        constraint = oracle.mode == FeedMode::Derived,
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        seeds = [Derivation::SEED, oracle.key().as_ref()],
+        bump,
+        has_one = oracle,
+    )]
+    derivation: Account<'info, Derivation>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct CloseDerivation<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        has_one = owner,
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        mut,
+        seeds = [Derivation::SEED, oracle.key().as_ref()],
+        bump,
+        has_one = oracle,
+        close = owner,
+    )]
+    derivation: Account<'info, Derivation>,
+}
+
+#[derive(Accounts)]
+pub struct InitMetadata<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        has_one = owner,
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        init,
+        payer = owner,
+        space = Metadata::SPACE,
+        seeds = [Metadata::SEED, oracle.key().as_ref()],
+        bump
+    )]
+    metadata: Account<'info, Metadata>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMetadata<'info> {
+    owner: Signer<'info>,
+    #[account(
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        has_one = owner,
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        mut,
+        seeds = [Metadata::SEED, oracle.key().as_ref()],
+        bump,
+        has_one = oracle,
+    )]
+    metadata: Account<'info, Metadata>,
+}
+
+#[derive(Accounts)]
+pub struct CloseMetadata<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        has_one = owner,
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(
+        mut,
+        seeds = [Metadata::SEED, oracle.key().as_ref()],
+        bump,
+        has_one = oracle,
+        close = owner,
+    )]
+    metadata: Account<'info, Metadata>,
+}
+
+#[derive(Accounts)]
+pub struct Migrate<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+    /// CHECK: older layouts cannot be deserialized as `Oracle`, the account
+    /// data is decoded by `Oracle::from_account_data` instead.
+    #[account(mut, owner = crate::ID)]
+    oracle: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+}
+
+/// Closes a root feed, its rent goes to `recipient`.
+#[derive(Accounts)]
+pub struct Delete<'info> {
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        has_one = owner,
+        constraint = oracle.is_root() @ Error::ParentMismatch,
+        constraint = oracle.children == 0 @ Error::OracleHasChildren,
+        constraint = oracle.mode == FeedMode::Direct @ Error::AggregatedOracle,
+        close = recipient,
+    )]
+    oracle: Account<'info, Oracle>,
+    /// CHECK: only receives the rent of the closed oracle.
+    #[account(mut)]
+    recipient: UncheckedAccount<'info>,
+}
+
+/// Closes a feed that was not written for [`Config::reap_after_secs`], such
+/// as a feed whose owner lost its key. The rent goes to the treasury.
+///
+/// Child feeds are detached from their `parent`, which must be passed for
+/// them. Feeds with children are reaped once their children are.
+///
+/// The metadata account of the feed is closed along with it if it exists.
+/// The aggregator and derivation accounts are closed too, they are required
+/// for feeds in the matching [`FeedMode`].
+#[derive(Accounts)]
+pub struct Reap<'info> {
+    admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [oracle.prefix_seed(), oracle.scope_seed(), &oracle.name_seed],
+        bump,
+        constraint = oracle.is_root() || parent.is_some() @ Error::ParentMismatch,
+        constraint = oracle.children == 0 @ Error::OracleHasChildren,
+        constraint = oracle.mode != FeedMode::Aggregate
+            || aggregator.is_some() @ Error::AggregatedOracle,
+        constraint = oracle.mode != FeedMode::Derived
+            || derivation.is_some() @ Error::AggregatedOracle,
+        close = treasury,
+    )]
+    oracle: Account<'info, Oracle>,
+    #[account(mut, address = oracle.parent @ Error::ParentMismatch)]
+    parent: Option<Account<'info, Oracle>>,
+    /// CHECK: the metadata PDA of the oracle, closed if it was initialized.
+    #[account(mut, seeds = [Metadata::SEED, oracle.key().as_ref()], bump)]
+    metadata: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [Aggregator::SEED, oracle.key().as_ref()],
+        bump,
+        close = treasury,
+    )]
+    aggregator: Option<Account<'info, Aggregator>>,
+    #[account(
+        mut,
+        seeds = [Derivation::SEED, oracle.key().as_ref()],
+        bump,
+        close = treasury,
+    )]
+    derivation: Option<Account<'info, Derivation>>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        has_one = admin @ Error::OwnerMismatch,
+    )]
+    config: Account<'info, Config>,
+    /// CHECK: only receives the rent, must match the configured treasury.
+    #[account(mut, address = config.treasury)]
+    treasury: UncheckedAccount<'info>,
+}
+
+/// Creates a fast oracle in the namespace of its owner, who must sign.
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct CreateFast<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+    owner: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = FastOracle::SPACE,
+        seeds = [FastOracle::SEED, owner.key().as_ref(), &name_seed(&name)],
+        bump
+    )]
+    oracle: AccountLoader<'info, FastOracle>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+    /// CHECK: only receives the fee, must match the configured treasury.
+    #[account(mut, address = config.treasury)]
+    treasury: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateFast<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(mut, has_one = owner)]
+    oracle: AccountLoader<'info, FastOracle>,
+    #[account(
+        seeds = [Config::SEED],
+        bump,
+        constraint = !config.paused @ Error::Paused,
+    )]
+    config: Account<'info, Config>,
+    /// CHECK: only receives the fee, must match the configured treasury.
+    #[account(mut, address = config.treasury)]
+    treasury: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DeleteFast<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(mut, has_one = owner, close = owner)]
+    oracle: AccountLoader<'info, FastOracle>,
 }
@@ -0,0 +1,70 @@
+//! Instructions of the native build of the program in `contract-native`.
+//!
+//! The native build does not use anchor, it decodes a borsh-encoded
+//! [`OracleInstruction`] from the instruction data instead. Its oracle
+//! accounts share the layout of [`Oracle`](crate::state::Oracle), including
+//! the anchor discriminator, so clients read accounts of both builds the same
+//! way. The [`instruction`] module builds the instructions along with their
+//! accounts.
+
+use anchor_lang::prelude::*;
+
+use crate::{Feed, FeedValue};
+
+pub mod instruction;
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize)]
+pub enum OracleInstruction {
+    /// Creates a root feed in the global namespace, funded with the rent
+    /// exempt minimum by the payer.
+    ///
+    /// Accounts: `[writable, signer]` payer, `[writable]` oracle derived from
+    /// `[name_seed(feed.name)]`, `[]` system program.
+    Create { feed: Feed },
+
+    /// Writes a new value to a feed.
+    ///
+    /// Accounts: `[signer]` owner, `[writable]` oracle.
+    Update {
+        value: Option<FeedValue>,
+        confidence: Option<u128>,
+    },
+
+    /// Closes a feed without children and refunds its lamports to the
+    /// recipient.
+    ///
+    /// Accounts: `[signer]` owner, `[writable]` oracle, `[writable]`
+    /// recipient.
+    Delete,
+
+    /// Proposes a new owner, who has to accept it, or withdraws the proposal.
+    ///
+    /// Accounts: `[signer]` owner, `[writable]` oracle.
+    ProposeOwner(Option<Pubkey>),
+
+    /// Accepts the ownership proposed to the signer.
+    ///
+    /// Accounts: `[signer]` pending owner, `[writable]` oracle.
+    AcceptOwner,
+
+    /// Creates a [`FastOracle`](crate::state::FastOracle) in the namespace
+    /// of its owner, funded with the rent exempt minimum by the payer.
+    ///
+    /// Accounts: `[writable, signer]` payer, `[signer]` owner, `[writable]`
+    /// oracle derived from `[b"fast", owner, name_seed(name)]`, `[]` system
+    /// program.
+    CreateFast { name: String, exponent: i8 },
+
+    /// Writes a new value to a fast oracle in place.
+    ///
+    /// Accounts: `[signer]` owner, `[writable]` oracle.
+    UpdateFast {
+        value: Option<FeedValue>,
+        confidence: Option<u128>,
+    },
+
+    /// Closes a fast oracle and refunds its lamports to the owner.
+    ///
+    /// Accounts: `[writable, signer]` owner, `[writable]` oracle.
+    DeleteFast,
+}
@@ -0,0 +1,184 @@
+//! Builders for the instructions of the native program.
+//!
+//! Each builder lists the accounts in the order the program expects them.
+//! [`create`] derives the address of the new oracle from its name, the other
+//! builders take the address of an existing oracle, see [`oracle_address`]
+//! and [`fast_oracle_address`].
+//! Every builder takes the id the native program is deployed at, which
+//! differs from [`crate::id`].
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        system_program,
+    },
+};
+
+use super::OracleInstruction;
+use crate::{
+    state::{name_seed, FastOracle},
+    Feed, FeedValue,
+};
+
+/// The address of the root feed `name` of the native program `program_id`.
+pub fn oracle_address(program_id: &Pubkey, name: &str) -> Pubkey {
+    Pubkey::find_program_address(&[&name_seed(name)], program_id).0
+}
+
+/// Creates the root feed described by `feed`, funded by `payer`.
+pub fn create(program_id: &Pubkey, payer: &Pubkey, feed: Feed) -> Instruction {
+    let oracle = oracle_address(program_id, &feed.name);
+    Instruction::new_with_borsh(
+        *program_id,
+        &OracleInstruction::Create { feed },
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(oracle, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+    )
+}
+
+/// Writes a new value to `oracle`, signed by its `owner`.
+pub fn update(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    oracle: &Pubkey,
+    value: Option<FeedValue>,
+    confidence: Option<u128>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &OracleInstruction::Update { value, confidence },
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(*oracle, false),
+        ],
+    )
+}
+
+/// Closes `oracle`, signed by its `owner`, and refunds its rent to
+/// `recipient`.
+pub fn delete(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    oracle: &Pubkey,
+    recipient: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &OracleInstruction::Delete,
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(*oracle, false),
+            AccountMeta::new(*recipient, false),
+        ],
+    )
+}
+
+/// Proposes `new_owner` as the owner of `oracle`, or withdraws the pending
+/// proposal if it is `None`.
+pub fn propose_owner(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    oracle: &Pubkey,
+    new_owner: Option<Pubkey>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &OracleInstruction::ProposeOwner(new_owner),
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(*oracle, false),
+        ],
+    )
+}
+
+/// Accepts the ownership of `oracle` proposed to `new_owner`.
+pub fn accept_owner(
+    program_id: &Pubkey,
+    new_owner: &Pubkey,
+    oracle: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &OracleInstruction::AcceptOwner,
+        vec![
+            AccountMeta::new_readonly(*new_owner, true),
+            AccountMeta::new(*oracle, false),
+        ],
+    )
+}
+
+/// The address of the fast oracle `name` of `owner` in the native program
+/// `program_id`.
+pub fn fast_oracle_address(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    name: &str,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[FastOracle::SEED, owner.as_ref(), &name_seed(name)],
+        program_id,
+    )
+    .0
+}
+
+/// Creates the fast oracle `name` of `owner`, funded by `payer`.
+pub fn create_fast(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    owner: &Pubkey,
+    name: &str,
+    exponent: i8,
+) -> Instruction {
+    let oracle = fast_oracle_address(program_id, owner, name);
+    Instruction::new_with_borsh(
+        *program_id,
+        &OracleInstruction::CreateFast {
+            name: name.to_owned(),
+            exponent,
+        },
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(oracle, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+    )
+}
+
+/// Writes a new value to the fast oracle `oracle`, signed by its `owner`.
+pub fn update_fast(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    oracle: &Pubkey,
+    value: Option<FeedValue>,
+    confidence: Option<u128>,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &OracleInstruction::UpdateFast { value, confidence },
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(*oracle, false),
+        ],
+    )
+}
+
+/// Closes the fast oracle `oracle` and refunds its rent to its `owner`.
+pub fn delete_fast(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    oracle: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_borsh(
+        *program_id,
+        &OracleInstruction::DeleteFast,
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(*oracle, false),
+        ],
+    )
+}
@@ -1,4 +1,12 @@
-use anchor_lang::{prelude::*, solana_program::clock::UnixTimestamp};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        clock::UnixTimestamp, entrypoint::MAX_PERMITTED_DATA_INCREASE,
+    },
+    Discriminator,
+};
+
+use crate::error::Error;
 
 pub type Timestamp = UnixTimestamp;
 
@@ -7,25 +15,1465 @@ pub type Timestamp = UnixTimestamp;
 #[account]
 #[derive(Debug)]
 pub struct Oracle {
+    /// The layout version of this account, see [`Oracle::VERSION`].
+    ///
+    /// Accounts created before layouts were versioned carry no version and are
+    /// decoded as [`OracleV0`].
+    pub version: u8,
+
     /// The owner of this feed.
     ///
     /// Only owners have write-access to feeds, including adding or removing child
     /// feeds, or setting a leaf value to some sequence of bytes.
     pub owner: Pubkey,
 
-    /// A UTF-8 encoded human-readable name of this feed.
+    /// The account proposed by the owner as the new owner of this feed.
+    ///
+    /// Ownership changes only once the proposed account accepts it, which
+    /// protects against transferring a feed to a mistyped address.
+    pub pending_owner: Option<Pubkey>,
+
+    /// The seed derived from `name`, which is used in the PDA derivation
+    /// along with the parent or namespace of the feed, see [`name_seed`].
+    pub name_seed: [u8; 32],
+
+    /// A UTF-8 encoded human-readable name of this feed, at most
+    /// [`Oracle::MAX_NAME_LEN`] bytes long.
     ///
-    /// This name is used in hash calculation along with its parent
     /// This name may contain only lowercase letters, digits 0-9 and dashes `-`.
-    pub name: [u8; 32],
+    pub name: String,
+
+    /// The address of the parent feed, or the default pubkey if this is a
+    /// root feed.
+    ///
+    /// Child feeds are derived from the address of their parent and their
+    /// name, see [`Oracle::scope_seed`] for root feeds.
+    pub parent: Pubkey,
+
+    /// The account whose namespace this feed was created in, or the default
+    /// pubkey for feeds in the global namespace. Child feeds inherit the
+    /// namespace of their parent.
+    ///
+    /// This is the owner at the time of creation, it does not change when
+    /// the ownership is transferred.
+    pub namespace: Pubkey,
+
+    /// The number of child feeds currently attached to this feed. A feed
+    /// cannot be deleted while it has children.
+    pub children: u32,
+
+    /// Determines who is allowed to write the value of this feed.
+    pub mode: FeedMode,
 
     /// A unix timestamp of the most recent update of the feed value.
     /// This value is provided by the validator sysclock account
     /// automatically during updates.
     pub updated_at: i64,
 
+    /// The slot of the most recent update of the feed value.
+    pub slot: u64,
+
+    /// The number of updates of the feed value so far, starting at one for
+    /// the value written at creation. Consecutive updates have consecutive
+    /// round ids.
+    pub round_id: u64,
+
     /// The value that is stored within a single feed. In most cases, intermediate
     /// nodes or non-leaf feeds will be None (although some may decide to have a
     /// summary value for their children), and leaf feeds will have concrete values.
+    pub value: Option<i128>,
+
+    /// The uncertainty of `value`, expressed with the same exponent. The actual
+    /// value is expected to lie within `value ± confidence`.
+    pub confidence: Option<u128>,
+
+    /// The running sum of `value * seconds` over the lifetime of the feed, up
+    /// to `updated_at`. Seconds without a value add nothing.
+    ///
+    /// The sum wraps around on overflow, so only the difference between two
+    /// observations is meaningful, see [`Oracle::observe`].
+    pub cumulative_value: i128,
+
+    /// The decimal exponent of the value, the value represented by this feed
+    /// is `value * 10^exponent`. Chosen when the feed is created.
+    pub exponent: i8,
+
+    /// How often the owner commits to writing this feed, see
+    /// [`Oracle::is_healthy`].
+    pub policy: UpdatePolicy,
+
+    /// The maximum number of entries retained in [`Oracle::history`].
+    ///
+    /// This value is chosen when the feed is created and determines the size
+    /// of the account, it cannot be changed afterwards.
+    pub history_capacity: u16,
+
+    /// Index of the oldest entry in `history` once the buffer is full. This is
+    /// the slot that will be overwritten by the next recorded value.
+    pub history_head: u16,
+
+    /// A ring buffer of the most recent values written to this feed, including
+    /// the current one. Use [`Oracle::history`] to iterate it in chronological
+    /// order.
+    pub history: Vec<HistoryEntry>,
+}
+
+/// The layout of oracle accounts before the round id of past values was
+/// recorded.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct OracleV7 {
+    pub version: u8,
+    pub owner: Pubkey,
+    pub pending_owner: Option<Pubkey>,
+    pub name_seed: [u8; 32],
+    pub name: String,
+    pub parent: Pubkey,
+    pub namespace: Pubkey,
+    pub children: u32,
+    pub mode: FeedMode,
+    pub updated_at: i64,
+    pub slot: u64,
+    pub round_id: u64,
+    pub value: Option<i128>,
+    pub confidence: Option<u128>,
+    pub cumulative_value: i128,
+    pub exponent: i8,
+    pub policy: UpdatePolicy,
+    pub history_capacity: u16,
+    pub history_head: u16,
+    pub history: Vec<HistoryEntryV7>,
+}
+
+impl From<OracleV7> for Oracle {
+    fn from(legacy: OracleV7) -> Self {
+        Self {
+            version: Self::VERSION,
+            owner: legacy.owner,
+            pending_owner: legacy.pending_owner,
+            name_seed: legacy.name_seed,
+            name: legacy.name,
+            parent: legacy.parent,
+            namespace: legacy.namespace,
+            children: legacy.children,
+            mode: legacy.mode,
+            updated_at: legacy.updated_at,
+            slot: legacy.slot,
+            round_id: legacy.round_id,
+            value: legacy.value,
+            confidence: legacy.confidence,
+            cumulative_value: legacy.cumulative_value,
+            exponent: legacy.exponent,
+            policy: legacy.policy,
+            history_capacity: legacy.history_capacity,
+            history_head: legacy.history_head,
+            history: legacy.history.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// The layout of oracle accounts before the confidence of past values was
+/// recorded.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct OracleV6 {
+    pub version: u8,
+    pub owner: Pubkey,
+    pub pending_owner: Option<Pubkey>,
+    pub name_seed: [u8; 32],
+    pub name: String,
+    pub parent: Pubkey,
+    pub namespace: Pubkey,
+    pub children: u32,
+    pub mode: FeedMode,
+    pub updated_at: i64,
+    pub slot: u64,
+    pub round_id: u64,
+    pub value: Option<i128>,
+    pub confidence: Option<u128>,
+    pub cumulative_value: i128,
+    pub exponent: i8,
+    pub policy: UpdatePolicy,
+    pub history_capacity: u16,
+    pub history_head: u16,
+    pub history: Vec<HistoryEntryV6>,
+}
+
+impl From<OracleV6> for Oracle {
+    fn from(legacy: OracleV6) -> Self {
+        Self {
+            version: Self::VERSION,
+            owner: legacy.owner,
+            pending_owner: legacy.pending_owner,
+            name_seed: legacy.name_seed,
+            name: legacy.name,
+            parent: legacy.parent,
+            namespace: legacy.namespace,
+            children: legacy.children,
+            mode: legacy.mode,
+            updated_at: legacy.updated_at,
+            slot: legacy.slot,
+            round_id: legacy.round_id,
+            value: legacy.value,
+            confidence: legacy.confidence,
+            cumulative_value: legacy.cumulative_value,
+            exponent: legacy.exponent,
+            policy: legacy.policy,
+            history_capacity: legacy.history_capacity,
+            history_head: legacy.history_head,
+            history: legacy.history.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// The layout of oracle accounts before names longer than 32 bytes were
+/// supported.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct OracleV5 {
+    pub version: u8,
+    pub owner: Pubkey,
+    pub pending_owner: Option<Pubkey>,
+    pub name: [u8; 32],
+    pub parent: Pubkey,
+    pub namespace: Pubkey,
+    pub children: u32,
+    pub mode: FeedMode,
+    pub updated_at: i64,
+    pub slot: u64,
+    pub round_id: u64,
+    pub value: Option<i128>,
+    pub confidence: Option<u128>,
+    pub cumulative_value: i128,
+    pub exponent: i8,
+    pub policy: UpdatePolicy,
+    pub history_capacity: u16,
+    pub history_head: u16,
+    pub history: Vec<HistoryEntryV6>,
+}
+
+impl From<OracleV5> for Oracle {
+    fn from(legacy: OracleV5) -> Self {
+        Self {
+            version: Self::VERSION,
+            owner: legacy.owner,
+            pending_owner: legacy.pending_owner,
+            name_seed: legacy.name,
+            name: name_from_seed(&legacy.name),
+            parent: legacy.parent,
+            namespace: legacy.namespace,
+            children: legacy.children,
+            mode: legacy.mode,
+            updated_at: legacy.updated_at,
+            slot: legacy.slot,
+            round_id: legacy.round_id,
+            value: legacy.value,
+            confidence: legacy.confidence,
+            cumulative_value: legacy.cumulative_value,
+            exponent: legacy.exponent,
+            policy: legacy.policy,
+            history_capacity: legacy.history_capacity,
+            history_head: legacy.history_head,
+            history: legacy.history.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// The layout of oracle accounts before owner namespaces were introduced.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct OracleV4 {
+    pub version: u8,
+    pub owner: Pubkey,
+    pub pending_owner: Option<Pubkey>,
+    pub name: [u8; 32],
+    pub parent: Pubkey,
+    pub children: u32,
+    pub mode: FeedMode,
+    pub updated_at: i64,
+    pub slot: u64,
+    pub round_id: u64,
+    pub value: Option<i128>,
+    pub confidence: Option<u128>,
+    pub cumulative_value: i128,
+    pub exponent: i8,
+    pub policy: UpdatePolicy,
+    pub history_capacity: u16,
+    pub history_head: u16,
+    pub history: Vec<HistoryEntryV6>,
+}
+
+impl From<OracleV4> for Oracle {
+    fn from(legacy: OracleV4) -> Self {
+        Self {
+            version: Self::VERSION,
+            owner: legacy.owner,
+            pending_owner: legacy.pending_owner,
+            name_seed: legacy.name,
+            name: name_from_seed(&legacy.name),
+            parent: legacy.parent,
+            namespace: Pubkey::default(),
+            children: legacy.children,
+            mode: legacy.mode,
+            updated_at: legacy.updated_at,
+            slot: legacy.slot,
+            round_id: legacy.round_id,
+            value: legacy.value,
+            confidence: legacy.confidence,
+            cumulative_value: legacy.cumulative_value,
+            exponent: legacy.exponent,
+            policy: legacy.policy,
+            history_capacity: legacy.history_capacity,
+            history_head: legacy.history_head,
+            history: legacy.history.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// The layout of oracle accounts before the value accumulator was kept.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct OracleV3 {
+    pub version: u8,
+    pub owner: Pubkey,
+    pub pending_owner: Option<Pubkey>,
+    pub name: [u8; 32],
+    pub parent: Pubkey,
+    pub children: u32,
+    pub mode: FeedMode,
+    pub updated_at: i64,
+    pub slot: u64,
+    pub round_id: u64,
+    pub value: Option<i128>,
+    pub confidence: Option<u128>,
+    pub exponent: i8,
+    pub policy: UpdatePolicy,
+    pub history_capacity: u16,
+    pub history_head: u16,
+    pub history: Vec<HistoryEntryV6>,
+}
+
+impl From<OracleV3> for Oracle {
+    fn from(legacy: OracleV3) -> Self {
+        Self {
+            version: Self::VERSION,
+            owner: legacy.owner,
+            pending_owner: legacy.pending_owner,
+            name_seed: legacy.name,
+            name: name_from_seed(&legacy.name),
+            parent: legacy.parent,
+            namespace: Pubkey::default(),
+            children: legacy.children,
+            mode: legacy.mode,
+            updated_at: legacy.updated_at,
+            slot: legacy.slot,
+            round_id: legacy.round_id,
+            value: legacy.value,
+            confidence: legacy.confidence,
+            cumulative_value: 0,
+            exponent: legacy.exponent,
+            policy: legacy.policy,
+            history_capacity: legacy.history_capacity,
+            history_head: legacy.history_head,
+            history: legacy.history.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// The layout of oracle accounts before slots and round ids were recorded.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct OracleV2 {
+    pub version: u8,
+    pub owner: Pubkey,
+    pub pending_owner: Option<Pubkey>,
+    pub name: [u8; 32],
+    pub parent: Pubkey,
+    pub children: u32,
+    pub mode: FeedMode,
+    pub updated_at: i64,
+    pub value: Option<i128>,
+    pub confidence: Option<u128>,
+    pub exponent: i8,
+    pub policy: UpdatePolicy,
+    pub history_capacity: u16,
+    pub history_head: u16,
+    pub history: Vec<HistoryEntryV6>,
+}
+
+impl From<OracleV2> for Oracle {
+    fn from(legacy: OracleV2) -> Self {
+        Self {
+            version: Self::VERSION,
+            owner: legacy.owner,
+            pending_owner: legacy.pending_owner,
+            name_seed: legacy.name,
+            name: name_from_seed(&legacy.name),
+            parent: legacy.parent,
+            namespace: Pubkey::default(),
+            children: legacy.children,
+            mode: legacy.mode,
+            updated_at: legacy.updated_at,
+            slot: 0,
+            round_id: 0,
+            value: legacy.value,
+            confidence: legacy.confidence,
+            cumulative_value: 0,
+            exponent: legacy.exponent,
+            policy: legacy.policy,
+            history_capacity: legacy.history_capacity,
+            history_head: legacy.history_head,
+            history: legacy.history.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// The layout of oracle accounts before update policies were introduced.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct OracleV1 {
+    pub version: u8,
+    pub owner: Pubkey,
+    pub pending_owner: Option<Pubkey>,
+    pub name: [u8; 32],
+    pub parent: Pubkey,
+    pub children: u32,
+    pub mode: FeedMode,
+    pub updated_at: i64,
+    pub value: Option<i128>,
+    pub confidence: Option<u128>,
+    pub exponent: i8,
+    pub history_capacity: u16,
+    pub history_head: u16,
+    pub history: Vec<HistoryEntryV6>,
+}
+
+impl From<OracleV1> for Oracle {
+    fn from(legacy: OracleV1) -> Self {
+        Self {
+            version: Self::VERSION,
+            owner: legacy.owner,
+            pending_owner: legacy.pending_owner,
+            name_seed: legacy.name,
+            name: name_from_seed(&legacy.name),
+            parent: legacy.parent,
+            namespace: Pubkey::default(),
+            children: legacy.children,
+            mode: legacy.mode,
+            updated_at: legacy.updated_at,
+            slot: 0,
+            round_id: 0,
+            value: legacy.value,
+            confidence: legacy.confidence,
+            cumulative_value: 0,
+            exponent: legacy.exponent,
+            policy: UpdatePolicy::default(),
+            history_capacity: legacy.history_capacity,
+            history_head: legacy.history_head,
+            history: legacy.history.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// The layout of oracle accounts created before layouts were versioned.
+///
+/// These accounts share the discriminator of [`Oracle`] and are told apart by
+/// their size. Use the `migrate` instruction to convert them.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct OracleV0 {
+    pub owner: Pubkey,
+    pub name: [u8; 32],
+    pub updated_at: i64,
     pub value: Option<u128>,
 }
+
+impl OracleV0 {
+    /// Borsh-encoded size of the legacy layout.
+    pub const LEN: usize = 32 + 32 + 8 + 17;
+}
+
+impl TryFrom<OracleV0> for Oracle {
+    type Error = Error;
+
+    fn try_from(legacy: OracleV0) -> std::result::Result<Self, Error> {
+        let value = legacy
+            .value
+            .map(i128::try_from)
+            .transpose()
+            .map_err(|_| Error::InvalidValue)?;
+
+        Ok(Self {
+            version: Self::VERSION,
+            owner: legacy.owner,
+            pending_owner: None,
+            name_seed: legacy.name,
+            name: name_from_seed(&legacy.name),
+            parent: Pubkey::default(),
+            namespace: Pubkey::default(),
+            children: 0,
+            mode: FeedMode::Direct,
+            updated_at: legacy.updated_at,
+            slot: 0,
+            round_id: 0,
+            value,
+            confidence: None,
+            cumulative_value: 0,
+            exponent: 0,
+            policy: UpdatePolicy::default(),
+            history_capacity: 0,
+            history_head: 0,
+            history: Vec::new(),
+        })
+    }
+}
+
+/// A reading of the value accumulator of a feed, see [`Oracle::observe`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct Observation {
+    /// [`Oracle::cumulative_value`] extended up to `timestamp`.
+    pub cumulative_value: i128,
+    pub timestamp: i64,
+}
+
+impl Observation {
+    /// The time-weighted average value of the feed between `earlier` and
+    /// this observation, or `None` if no time passed in between.
+    pub fn twap_since(&self, earlier: &Observation) -> Option<i128> {
+        let elapsed = self.timestamp.checked_sub(earlier.timestamp)?;
+        if elapsed <= 0 {
+            return None;
+        }
+        let sum = self.cumulative_value.wrapping_sub(earlier.cumulative_value);
+        Some(sum / elapsed as i128)
+    }
+}
+
+/// The way the value of a feed is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub enum FeedMode {
+    /// The owner writes the value directly.
+    Direct,
+
+    /// The value is the median of the submissions of the publishers listed in
+    /// the [`Aggregator`] account of this feed.
+    Aggregate,
+
+    /// The value is computed from other feeds as defined in the
+    /// [`Derivation`] account of this feed.
+    Derived,
+}
+
+/// The update schedule a feed owner commits to when creating the feed.
+///
+/// The owner is expected to write the feed at least every `heartbeat_secs`
+/// seconds, and in between whenever the value moves by more than
+/// `deviation_bps` basis points.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    AnchorSerialize,
+    AnchorDeserialize,
+)]
+pub struct UpdatePolicy {
+    /// Maximum number of seconds between two updates, zero if the feed has
+    /// no heartbeat.
+    pub heartbeat_secs: u32,
+
+    /// Smallest move of the value, in basis points of the current value,
+    /// that warrants an update before the next heartbeat. Zero means every
+    /// change does.
+    pub deviation_bps: u16,
+}
+
+impl UpdatePolicy {
+    /// Returns true if moving from `current` to `value` crosses the deviation
+    /// threshold.
+    pub fn deviates(&self, current: i128, value: i128) -> bool {
+        let diff = match value.checked_sub(current) {
+            Some(diff) => diff.unsigned_abs(),
+            None => return true,
+        };
+        match diff.checked_mul(10_000) {
+            Some(scaled) => {
+                scaled
+                    > current
+                        .unsigned_abs()
+                        .saturating_mul(self.deviation_bps as u128)
+            }
+            None => true,
+        }
+    }
+}
+
+/// A single historical value of a feed along with its confidence, the time
+/// and slot it was written at and its round id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct HistoryEntry {
+    pub value: Option<i128>,
+    pub confidence: Option<u128>,
+    pub updated_at: i64,
+    pub slot: u64,
+
+    /// The [`Oracle::round_id`] of the value, zero for entries recorded
+    /// before the history kept round ids.
+    pub round_id: u64,
+}
+
+impl HistoryEntry {
+    /// Borsh-encoded size of a single entry.
+    pub const LEN: usize = 17 + 17 + 8 + 8 + 8;
+}
+
+/// The layout of history entries of [`OracleV7`], before the round id of
+/// past values was recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct HistoryEntryV7 {
+    pub value: Option<i128>,
+    pub confidence: Option<u128>,
+    pub updated_at: i64,
+    pub slot: u64,
+}
+
+impl From<HistoryEntryV7> for HistoryEntry {
+    fn from(legacy: HistoryEntryV7) -> Self {
+        Self {
+            value: legacy.value,
+            confidence: legacy.confidence,
+            updated_at: legacy.updated_at,
+            slot: legacy.slot,
+            round_id: 0,
+        }
+    }
+}
+
+/// The layout of history entries up to [`OracleV6`], before the confidence
+/// of past values was recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct HistoryEntryV6 {
+    pub value: Option<i128>,
+    pub updated_at: i64,
+    pub slot: u64,
+}
+
+impl From<HistoryEntryV6> for HistoryEntry {
+    fn from(legacy: HistoryEntryV6) -> Self {
+        Self {
+            value: legacy.value,
+            confidence: None,
+            updated_at: legacy.updated_at,
+            slot: legacy.slot,
+            round_id: 0,
+        }
+    }
+}
+
+/// The seed a feed name contributes to the PDA derivation of the feed: the
+/// name itself zero-padded to 32 bytes, or the sha256 hash of the name if it
+/// is longer than 32 bytes.
+pub fn name_seed(name: &str) -> [u8; 32] {
+    let bytes = name.as_bytes();
+    if bytes.len() > 32 {
+        return anchor_lang::solana_program::hash::hash(bytes).to_bytes();
+    }
+    let mut seed = [0; 32];
+    seed[..bytes.len()].copy_from_slice(bytes);
+    seed
+}
+
+/// The address of the feed at the dotted `path` in the feeds tree, whose root
+/// feed is derived from the `scope` seeds followed by its name seed.
+///
+/// Path segments are separated by dots, so `crypto.sol.usdt` is the feed
+/// `usdt` attached to `sol`, which in turn is attached to the root feed
+/// `crypto`. Feed names cannot contain dots themselves.
+pub fn find_path_address(scope: &[&[u8]], path: &str) -> Pubkey {
+    let mut segments = path.split('.');
+    let root = name_seed(segments.next().unwrap_or_default());
+    let root_seeds = [scope, &[&root[..]]].concat();
+    let root_id = Pubkey::find_program_address(&root_seeds, &crate::ID).0;
+    segments.fold(root_id, |parent, segment| {
+        Pubkey::find_program_address(
+            &[parent.as_ref(), &name_seed(segment)],
+            &crate::ID,
+        )
+        .0
+    })
+}
+
+/// Recovers the name of a feed created before names were stored separately,
+/// when the seed was the zero-padded name.
+fn name_from_seed(seed: &[u8; 32]) -> String {
+    let len = seed.iter().position(|b| *b == 0).unwrap_or(seed.len());
+    String::from_utf8_lossy(&seed[..len]).into_owned()
+}
+
+impl Oracle {
+    /// The prefix of the PDA seeds of root feeds in an owner namespace.
+    pub const SEED: &'static [u8] = b"oracle";
+
+    /// The layout version of accounts written by this program.
+    pub const VERSION: u8 = 8;
+
+    /// Upper bound on the length of a feed name, in bytes.
+    pub const MAX_NAME_LEN: usize = 128;
+
+    /// Upper bound on the history length, keeps an account with the longest
+    /// name within the size that can be allocated from within a program.
+    pub const MAX_HISTORY_CAPACITY: u16 = ((MAX_PERMITTED_DATA_INCREASE
+        - 8
+        - Self::BASE_LEN
+        - Self::MAX_NAME_LEN)
+        / HistoryEntry::LEN) as u16;
+
+    /// Borsh-encoded size of an oracle with an empty name and history.
+    pub const BASE_LEN: usize = 1 + 32 + 33 + 32 + 4 + 32 + 32 + 4 + 1 + 8
+        + 8 + 8 + 17 + 17 + 16 + 1 + 6 + 2 + 2 + 4;
+
+    /// Number of bytes needed for an oracle account (including the anchor
+    /// discriminator) named with `name_len` bytes that retains up to
+    /// `history_capacity` past values.
+    pub const fn space(name_len: usize, history_capacity: u16) -> usize {
+        8 + Self::BASE_LEN
+            + name_len
+            + history_capacity as usize * HistoryEntry::LEN
+    }
+
+    /// A new root feed in the global namespace without a value, as the
+    /// native build of the program initializes it.
+    pub fn new(
+        owner: Pubkey,
+        name: String,
+        exponent: i8,
+        policy: UpdatePolicy,
+        history_capacity: u16,
+    ) -> Self {
+        Self {
+            version: Self::VERSION,
+            owner,
+            pending_owner: None,
+            name_seed: name_seed(&name),
+            name,
+            parent: Pubkey::default(),
+            namespace: Pubkey::default(),
+            children: 0,
+            mode: FeedMode::Direct,
+            updated_at: 0,
+            slot: 0,
+            round_id: 0,
+            value: None,
+            confidence: None,
+            cumulative_value: 0,
+            exponent,
+            policy,
+            history_capacity,
+            history_head: 0,
+            history: Vec::new(),
+        }
+    }
+
+    /// The layout version of raw account data (including the discriminator),
+    /// or `None` if it is not an oracle account.
+    pub fn layout_version(data: &[u8]) -> Option<u8> {
+        if data.len() <= 8 || data[..8] != Self::discriminator() {
+            return None;
+        }
+        match data.len() - 8 {
+            OracleV0::LEN => Some(0),
+            _ => Some(data[8]),
+        }
+    }
+
+    /// Decodes raw oracle account data of any layout version, converting
+    /// older layouts to the current one.
+    pub fn from_account_data(
+        data: &[u8],
+    ) -> std::result::Result<Self, Error> {
+        let mut payload = &data[8.min(data.len())..];
+        match Self::layout_version(data) {
+            Some(0) => OracleV0::deserialize(&mut payload)
+                .map_err(|_| Error::InvalidOracle)?
+                .try_into(),
+            Some(1) => OracleV1::deserialize(&mut payload)
+                .map(Self::from)
+                .map_err(|_| Error::InvalidOracle),
+            Some(2) => OracleV2::deserialize(&mut payload)
+                .map(Self::from)
+                .map_err(|_| Error::InvalidOracle),
+            Some(3) => OracleV3::deserialize(&mut payload)
+                .map(Self::from)
+                .map_err(|_| Error::InvalidOracle),
+            Some(4) => OracleV4::deserialize(&mut payload)
+                .map(Self::from)
+                .map_err(|_| Error::InvalidOracle),
+            Some(5) => OracleV5::deserialize(&mut payload)
+                .map(Self::from)
+                .map_err(|_| Error::InvalidOracle),
+            Some(6) => OracleV6::deserialize(&mut payload)
+                .map(Self::from)
+                .map_err(|_| Error::InvalidOracle),
+            Some(7) => OracleV7::deserialize(&mut payload)
+                .map(Self::from)
+                .map_err(|_| Error::InvalidOracle),
+            Some(Self::VERSION) => Self::deserialize(&mut payload)
+                .map_err(|_| Error::InvalidOracle),
+            Some(_) => Err(Error::InvalidState),
+            None => Err(Error::InvalidOracle),
+        }
+    }
+
+    /// Encodes this oracle as raw account data prefixed with the anchor
+    /// discriminator, the inverse of [`Oracle::from_account_data`] for the
+    /// current layout.
+    pub fn to_account_data(&self) -> Vec<u8> {
+        let mut data = Self::discriminator().to_vec();
+        self.serialize(&mut data)
+            .expect("serializing to a vec never fails");
+        data
+    }
+
+    /// Returns true if this feed is not attached to a parent.
+    pub fn is_root(&self) -> bool {
+        self.parent == Pubkey::default()
+    }
+
+    /// Returns true if this is a root feed created in the namespace of an
+    /// owner rather than in the global namespace.
+    pub fn is_namespaced_root(&self) -> bool {
+        self.is_root() && self.namespace != Pubkey::default()
+    }
+
+    /// The first seed in the PDA derivation of this feed, [`Oracle::SEED`]
+    /// for root feeds in an owner namespace and empty otherwise.
+    ///
+    /// Empty seeds do not contribute to the address hash, so
+    /// `[prefix_seed, scope_seed, name_seed]` derives every kind of feed:
+    /// `[name_seed]` in the global namespace, `[b"oracle", namespace,
+    /// name_seed]` in an owner namespace and `[parent, name_seed]` for child
+    /// feeds.
+    pub fn prefix_seed(&self) -> &[u8] {
+        if self.is_namespaced_root() {
+            Self::SEED
+        } else {
+            &[]
+        }
+    }
+
+    /// The parent of child feeds, or the namespace of root feeds, which is the
+    /// default pubkey in the global namespace.
+    pub fn scope(&self) -> Pubkey {
+        if self.is_root() {
+            self.namespace
+        } else {
+            self.parent
+        }
+    }
+
+    /// The seed that scopes the name in the PDA derivation of this feed: the
+    /// parent of child feeds, the namespace of namespaced root feeds and
+    /// empty for root feeds in the global namespace.
+    pub fn scope_seed(&self) -> &[u8] {
+        if !self.is_root() {
+            self.parent.as_ref()
+        } else if self.is_namespaced_root() {
+            self.namespace.as_ref()
+        } else {
+            &[]
+        }
+    }
+
+    /// Returns true if the feed has a value that was written within its
+    /// heartbeat as of the unix timestamp `now`. Feeds without a heartbeat
+    /// are healthy as long as they have a value.
+    pub fn is_healthy(&self, now: i64) -> bool {
+        let heartbeat = self.policy.heartbeat_secs as i64;
+        self.value.is_some()
+            && (heartbeat == 0
+                || now.saturating_sub(self.updated_at) <= heartbeat)
+    }
+
+    /// Reads the value accumulator as of the unix timestamp `now`, as if the
+    /// current value was held until then.
+    pub fn observe(&self, now: i64) -> Observation {
+        let held = now.saturating_sub(self.updated_at).max(0) as i128;
+        let added = self.value.unwrap_or(0).wrapping_mul(held);
+        Observation {
+            cumulative_value: self.cumulative_value.wrapping_add(added),
+            timestamp: now,
+        }
+    }
+
+    /// Sets the current value of the feed and appends it to the history,
+    /// evicting the oldest entry if the buffer is full.
+    pub fn record(
+        &mut self,
+        value: Option<i128>,
+        confidence: Option<u128>,
+        clock: &Clock,
+    ) {
+        self.cumulative_value =
+            self.observe(clock.unix_timestamp).cumulative_value;
+        self.value = value;
+        self.confidence = confidence;
+        self.updated_at = clock.unix_timestamp;
+        self.slot = clock.slot;
+        self.round_id = self.round_id.saturating_add(1);
+
+        if self.history_capacity == 0 {
+            return;
+        }
+
+        let entry = HistoryEntry {
+            value,
+            confidence,
+            updated_at: clock.unix_timestamp,
+            slot: clock.slot,
+            round_id: self.round_id,
+        };
+
+        if self.history.len() < self.history_capacity as usize {
+            self.history.push(entry);
+        } else {
+            self.history[self.history_head as usize] = entry;
+            self.history_head = (self.history_head + 1) % self.history_capacity;
+        }
+    }
+
+    /// Iterates over the retained history from the oldest to the most recent
+    /// entry.
+    pub fn history(&self) -> impl Iterator<Item = &HistoryEntry> {
+        let (newer, older) = self.history.split_at(self.history_head as usize);
+        older.iter().chain(newer.iter())
+    }
+
+    /// Lowers the history capacity to at most `capacity`, dropping the oldest
+    /// entries that no longer fit.
+    pub fn shrink_history(&mut self, capacity: u16) {
+        if self.history_capacity <= capacity {
+            return;
+        }
+        let mut history: Vec<HistoryEntry> = self.history().copied().collect();
+        history.drain(..history.len().saturating_sub(capacity as usize));
+        self.history = history;
+        self.history_head = 0;
+        self.history_capacity = capacity;
+    }
+}
+
+/// A feed in a fixed-size account that programs access in place, without
+/// decoding and encoding the whole account as for [`Oracle`].
+///
+/// Meant for feeds that are written every slot: it keeps only the latest
+/// value and the value accumulator, no name, history or children. Stored in
+/// a PDA derived from `[b"fast", owner, name_seed]`.
+#[repr(C)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, bytemuck::Pod, bytemuck::Zeroable,
+)]
+pub struct FastOracle {
+    pub owner: Pubkey,
+
+    /// The seed derived from the name of the feed, see [`name_seed`].
+    pub name_seed: [u8; 32],
+
+    /// Little endian encoding of the current value, see
+    /// [`FastOracle::value`]. 128 bit integers are stored as bytes, their
+    /// alignment differs between targets.
+    pub value: [u8; 16],
+
+    /// Little endian encoding of the confidence, see
+    /// [`FastOracle::confidence`].
+    pub confidence: [u8; 16],
+
+    /// Little endian encoding of the value accumulator, see
+    /// [`Oracle::cumulative_value`].
+    pub cumulative_value: [u8; 16],
+
+    pub updated_at: i64,
+    pub slot: u64,
+    pub round_id: u64,
+
+    /// Bit 0 is set if the feed has a value, bit 1 if it has a confidence.
+    pub flags: u8,
+
+    pub exponent: i8,
+
+    /// Keeps the size a multiple of the alignment, so the struct has no
+    /// implicit padding.
+    pub padding: [u8; 6],
+}
+
+impl FastOracle {
+    /// The prefix of the PDA seeds of fast oracles.
+    pub const SEED: &'static [u8] = b"fast";
+
+    /// The anchor discriminator of fast oracle accounts, the first 8 bytes of
+    /// `sha256("account:FastOracle")`.
+    pub const DISCRIMINATOR: [u8; 8] = [142, 216, 198, 244, 185, 222, 126, 155];
+
+    /// Number of bytes needed for a fast oracle account (including the anchor
+    /// discriminator).
+    pub const SPACE: usize = 8 + std::mem::size_of::<Self>();
+
+    const HAS_VALUE: u8 = 1;
+    const HAS_CONFIDENCE: u8 = 2;
+
+    pub fn value(&self) -> Option<i128> {
+        (self.flags & Self::HAS_VALUE != 0)
+            .then(|| i128::from_le_bytes(self.value))
+    }
+
+    pub fn confidence(&self) -> Option<u128> {
+        (self.flags & Self::HAS_CONFIDENCE != 0)
+            .then(|| u128::from_le_bytes(self.confidence))
+    }
+
+    /// Reads the value accumulator as of the unix timestamp `now`, see
+    /// [`Oracle::observe`].
+    pub fn observe(&self, now: i64) -> Observation {
+        let held = now.saturating_sub(self.updated_at).max(0) as i128;
+        let added = self.value().unwrap_or(0).wrapping_mul(held);
+        Observation {
+            cumulative_value: i128::from_le_bytes(self.cumulative_value)
+                .wrapping_add(added),
+            timestamp: now,
+        }
+    }
+
+    /// Sets the current value of the feed.
+    pub fn record(
+        &mut self,
+        value: Option<i128>,
+        confidence: Option<u128>,
+        clock: &Clock,
+    ) {
+        self.cumulative_value = self
+            .observe(clock.unix_timestamp)
+            .cumulative_value
+            .to_le_bytes();
+        self.value = value.unwrap_or(0).to_le_bytes();
+        self.confidence = confidence.unwrap_or(0).to_le_bytes();
+        self.flags = value.map_or(0, |_| Self::HAS_VALUE)
+            | confidence.map_or(0, |_| Self::HAS_CONFIDENCE);
+        self.updated_at = clock.unix_timestamp;
+        self.slot = clock.slot;
+        self.round_id = self.round_id.saturating_add(1);
+    }
+}
+
+impl anchor_lang::Discriminator for FastOracle {
+    const DISCRIMINATOR: [u8; 8] = FastOracle::DISCRIMINATOR;
+}
+
+impl anchor_lang::Owner for FastOracle {
+    fn owner() -> Pubkey {
+        crate::ID
+    }
+}
+
+impl anchor_lang::ZeroCopy for FastOracle {}
+
+/// The set of publishers of a feed in [`FeedMode::Aggregate`] mode along with
+/// their latest submissions.
+///
+/// Stored in a PDA derived from `[b"aggregator", oracle]`.
+#[account]
+#[derive(Debug)]
+pub struct Aggregator {
+    /// The oracle whose value is aggregated.
+    pub oracle: Pubkey,
+
+    /// Minimum number of fresh submissions required to update the value.
+    pub quorum: u8,
+
+    /// Submissions older than this number of slots are ignored.
+    pub max_age_slots: u64,
+
+    /// The latest submission of every authorized publisher.
+    pub submissions: Vec<Submission>,
+}
+
+/// The latest value submitted by a single publisher.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct Submission {
+    pub publisher: Pubkey,
+    pub value: i128,
+
+    /// The slot of the submission, zero if the publisher never submitted.
+    pub slot: u64,
+}
+
+impl Submission {
+    /// Borsh-encoded size of a single submission.
+    pub const LEN: usize = 32 + 16 + 8;
+}
+
+impl Aggregator {
+    pub const SEED: &'static [u8] = b"aggregator";
+
+    /// Upper bound on the number of publishers of a single feed.
+    pub const MAX_PUBLISHERS: usize = 32;
+
+    /// Number of bytes needed for an aggregator account (including the anchor
+    /// discriminator) of `publishers` publishers.
+    pub const fn space(publishers: usize) -> usize {
+        8 + 32 + 1 + 8 + 4 + publishers * Submission::LEN
+    }
+
+    /// The latest submission of `publisher`, if it is authorized.
+    pub fn submission_mut(
+        &mut self,
+        publisher: &Pubkey,
+    ) -> Option<&mut Submission> {
+        self.submissions
+            .iter_mut()
+            .find(|s| &s.publisher == publisher)
+    }
+
+    /// The median of the submissions made within `max_age_slots` of `slot`
+    /// along with half of their spread as the confidence, or `None` if there
+    /// are fewer of them than the quorum.
+    pub fn aggregate(&self, slot: u64) -> Option<(i128, u128)> {
+        let mut fresh: Vec<i128> = self
+            .submissions
+            .iter()
+            .filter(|s| {
+                s.slot != 0
+                    && slot.saturating_sub(s.slot) <= self.max_age_slots
+            })
+            .map(|s| s.value)
+            .collect();
+
+        if fresh.is_empty() || fresh.len() < self.quorum as usize {
+            return None;
+        }
+
+        fresh.sort_unstable();
+        let mid = fresh.len() / 2;
+        let median = match fresh.len() % 2 {
+            1 => fresh[mid],
+            _ => {
+                let (a, b) = (fresh[mid - 1], fresh[mid]);
+                a / 2 + b / 2 + (a % 2 + b % 2) / 2
+            }
+        };
+        // max >= min, so the wrapped difference is exact when read as u128
+        let spread = fresh[fresh.len() - 1].wrapping_sub(fresh[0]) as u128;
+        Some((median, spread / 2))
+    }
+}
+
+/// The definition of a feed in [`FeedMode::Derived`] mode.
+///
+/// Stored in a PDA derived from `[b"derivation", oracle]`.
+#[account]
+#[derive(Debug)]
+pub struct Derivation {
+    /// The oracle whose value is derived.
+    pub oracle: Pubkey,
+
+    /// How the value is computed from the source oracles.
+    pub expression: Expression,
+}
+
+impl Derivation {
+    pub const SEED: &'static [u8] = b"derivation";
+
+    /// Upper bound on the number of sources of a single feed.
+    pub const MAX_SOURCES: usize = 8;
+
+    /// Number of bytes needed for a derivation account (including the anchor
+    /// discriminator) whose expression reads `sources` oracles.
+    pub const fn space(sources: usize) -> usize {
+        8 + 32 + 1 + 4 + sources * (32 + 4)
+    }
+}
+
+/// A computation over the values of other oracles.
+///
+/// Sources are combined by their real values, that is with their exponents
+/// applied, and the result is expressed with the exponent of the derived feed.
+#[derive(Debug, Clone, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub enum Expression {
+    /// `numerator / denominator`
+    Ratio {
+        numerator: Pubkey,
+        denominator: Pubkey,
+    },
+
+    /// `a * b`
+    Product { a: Pubkey, b: Pubkey },
+
+    /// `1 / source`
+    Inverse { source: Pubkey },
+
+    /// The sum of the sources, each multiplied by its weight in basis points.
+    /// Weights summing up to 10 000 make a weighted average.
+    WeightedSum { terms: Vec<WeightedSource> },
+}
+
+/// A term of [`Expression::WeightedSum`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct WeightedSource {
+    pub source: Pubkey,
+    pub weight_bps: i32,
+}
+
+impl Expression {
+    /// The oracles read by the expression, in the order their values are
+    /// passed to [`Expression::evaluate`].
+    pub fn sources(&self) -> Vec<Pubkey> {
+        match self {
+            Expression::Ratio {
+                numerator,
+                denominator,
+            } => vec![*numerator, *denominator],
+            Expression::Product { a, b } => vec![*a, *b],
+            Expression::Inverse { source } => vec![*source],
+            Expression::WeightedSum { terms } => {
+                terms.iter().map(|t| t.source).collect()
+            }
+        }
+    }
+
+    /// Computes the value of the expression with the given `exponent` from
+    /// the `(value, exponent)` pairs of its sources.
+    ///
+    /// Returns `None` on division by zero or if an intermediate result does
+    /// not fit into an `i128`.
+    pub fn evaluate(
+        &self,
+        sources: &[(i128, i8)],
+        exponent: i8,
+    ) -> Option<i128> {
+        let exponent = exponent as i32;
+        match self {
+            Expression::Ratio { .. } => {
+                let [(a, ea), (b, eb)] = [sources[0], sources[1]];
+                let shift = ea as i32 - eb as i32 - exponent;
+                if shift >= 0 {
+                    a.checked_mul(pow10(shift)?)?.checked_div(b)
+                } else {
+                    a.checked_div(b.checked_mul(pow10(-shift)?)?)
+                }
+            }
+            Expression::Product { .. } => {
+                let [(a, ea), (b, eb)] = [sources[0], sources[1]];
+                rescale(a.checked_mul(b)?, ea as i32 + eb as i32 - exponent)
+            }
+            Expression::Inverse { .. } => {
+                let (a, ea) = sources[0];
+                let shift = -(ea as i32) - exponent;
+                if shift >= 0 {
+                    pow10(shift)?.checked_div(a)
+                } else {
+                    1i128.checked_div(a.checked_mul(pow10(-shift)?)?)
+                }
+            }
+            Expression::WeightedSum { terms } => terms
+                .iter()
+                .zip(sources)
+                .try_fold(0i128, |sum, (term, &(value, e))| {
+                    let weighted = value.checked_mul(term.weight_bps as i128)?;
+                    let scaled = rescale(weighted, e as i32 - exponent)?;
+                    sum.checked_add(scaled)
+                })
+                .map(|sum| sum / 10_000),
+        }
+    }
+}
+
+fn pow10(exponent: i32) -> Option<i128> {
+    10i128.checked_pow(exponent.try_into().ok()?)
+}
+
+/// Multiplies `value` by `10^shift`, truncating towards zero for negative
+/// shifts.
+fn rescale(value: i128, shift: i32) -> Option<i128> {
+    if shift >= 0 {
+        value.checked_mul(pow10(shift)?)
+    } else {
+        // values beyond 10^38 fit no i128, so the result is zero
+        Some(pow10(-shift).map_or(0, |d| value / d))
+    }
+}
+
+/// Protocol-wide settings, stored in a singleton PDA derived from
+/// `[b"config"]`.
+#[account]
+#[derive(Debug)]
+pub struct Config {
+    /// The account allowed to change these settings.
+    pub admin: Pubkey,
+
+    /// While set, no feed can be created or written.
+    pub paused: bool,
+
+    /// The account that receives the fees.
+    pub treasury: Pubkey,
+
+    /// Lamports charged to the payer for every feed created.
+    pub create_fee: u64,
+
+    /// Lamports charged to the owner for every value written.
+    pub update_fee: u64,
+
+    /// Lamports charged to the requester for every [`Request`] opened.
+    pub request_fee: u64,
+
+    /// The id assigned to the next [`Request`].
+    pub next_request_id: u64,
+
+    /// Root feeds not written for this many seconds are considered abandoned
+    /// and can be closed by the admin, zero disables reaping.
+    pub reap_after_secs: i64,
+
+    /// While set, only the admin can create feeds in the global namespace.
+    /// Feeds in the namespace of their owner can always be created.
+    pub curated: bool,
+}
+
+impl Config {
+    pub const SEED: &'static [u8] = b"config";
+
+    /// Number of bytes needed for the config account (including the anchor
+    /// discriminator).
+    pub const SPACE: usize = 8 + 32 + 1 + 32 + 8 + 8 + 8 + 8 + 8 + 1;
+}
+
+/// A request for a fresh value of a feed, opened by a consumer and fulfilled
+/// by the owner of the feed.
+///
+/// Stored in a PDA derived from `[b"request", id]`, where `id` is the little
+/// endian encoding of [`Request::id`].
+#[account]
+#[derive(Debug)]
+pub struct Request {
+    /// Sequential id of the request, taken from [`Config::next_request_id`].
+    pub id: u64,
+
+    /// The oracle whose value is requested.
+    pub oracle: Pubkey,
+
+    /// The account that opened the request, it receives the rent back when
+    /// the request is closed.
+    pub requester: Pubkey,
+
+    /// A unix timestamp of the time the request was opened.
+    pub requested_at: i64,
+
+    /// A unix timestamp of the time the request was fulfilled, `None` while it
+    /// is pending.
+    pub fulfilled_at: Option<i64>,
+
+    /// The value the request was fulfilled with.
+    pub value: Option<i128>,
+
+    /// The confidence of `value`.
+    pub confidence: Option<u128>,
+
+    /// The round id of the oracle update that fulfilled the request.
+    pub round_id: u64,
+}
+
+impl Request {
+    pub const SEED: &'static [u8] = b"request";
+
+    /// Number of bytes needed for a request account (including the anchor
+    /// discriminator).
+    pub const SPACE: usize = 8 + 8 + 32 + 32 + 8 + 9 + 17 + 17 + 8;
+
+    pub fn is_fulfilled(&self) -> bool {
+        self.fulfilled_at.is_some()
+    }
+}
+
+/// Descriptive information about a feed, edited by the owner of the feed.
+///
+/// Stored in a PDA derived from `[b"metadata", oracle]`.
+#[account]
+#[derive(Debug)]
+pub struct Metadata {
+    /// The oracle being described.
+    pub oracle: Pubkey,
+
+    pub info: FeedMetadata,
+}
+
+impl Metadata {
+    pub const SEED: &'static [u8] = b"metadata";
+
+    /// Number of bytes needed for a metadata account (including the anchor
+    /// discriminator), large enough for any valid [`FeedMetadata`].
+    pub const SPACE: usize = 8 + 32 + FeedMetadata::MAX_LEN;
+}
+
+/// The descriptive fields of a feed, all of them optional. Empty strings and
+/// lists mean the field is not set.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    AnchorSerialize,
+    AnchorDeserialize,
+)]
+pub struct FeedMetadata {
+    /// What the value of the feed represents.
+    pub description: String,
+
+    /// The unit of the value, e.g. `USD` or `°C`.
+    pub unit: String,
+
+    /// The number of decimal places the value is meant to be displayed with.
+    pub decimals: u8,
+
+    /// Where the value comes from, e.g. the names of exchanges or APIs.
+    pub sources: Vec<String>,
+
+    /// Category tags, e.g. `crypto` or `weather`.
+    pub tags: Vec<String>,
+
+    /// A URL with more information about the feed.
+    pub website: String,
+}
+
+impl FeedMetadata {
+    pub const MAX_DESCRIPTION_LEN: usize = 256;
+
+    pub const MAX_UNIT_LEN: usize = 16;
+
+    /// Upper bound on the number of entries in `sources` and in `tags`.
+    pub const MAX_ENTRIES: usize = 8;
+
+    /// Upper bound on the length of a single source or tag.
+    pub const MAX_ENTRY_LEN: usize = 32;
+
+    pub const MAX_WEBSITE_LEN: usize = 128;
+
+    /// Borsh-encoded size of metadata with every field at its limit.
+    pub const MAX_LEN: usize = 4
+        + Self::MAX_DESCRIPTION_LEN
+        + 4
+        + Self::MAX_UNIT_LEN
+        + 1
+        + 2 * (4 + Self::MAX_ENTRIES * (4 + Self::MAX_ENTRY_LEN))
+        + 4
+        + Self::MAX_WEBSITE_LEN;
+
+    /// Checks that every field is within its limit, string lengths are
+    /// counted in bytes.
+    pub fn validate(&self) -> std::result::Result<(), Error> {
+        let entries_fit = |entries: &[String]| {
+            entries.len() <= Self::MAX_ENTRIES
+                && entries.iter().all(|e| e.len() <= Self::MAX_ENTRY_LEN)
+        };
+        if self.description.len() <= Self::MAX_DESCRIPTION_LEN
+            && self.unit.len() <= Self::MAX_UNIT_LEN
+            && entries_fit(&self.sources)
+            && entries_fit(&self.tags)
+            && self.website.len() <= Self::MAX_WEBSITE_LEN
+        {
+            Ok(())
+        } else {
+            Err(Error::InvalidMetadata)
+        }
+    }
+}
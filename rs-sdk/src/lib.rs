@@ -3,11 +3,26 @@ use near::NearNetwork;
 use vvtec::state::Timestamp;
 use solana::SolanaNetwork;
 
+pub use vvtec::state::{FeedMetadata, Observation};
+
 pub mod solana;
 pub mod near;
 
+/// The latest value of a feed.
 #[derive(Debug)]
-pub struct OracleResponse(pub Option<u128>, pub Timestamp);
+pub struct OracleResponse(
+    pub Option<i128>,
+    /// Uncertainty of the value, which is expected to lie within
+    /// `value ± confidence`.
+    pub Option<u128>,
+    /// Time of the most recent update.
+    pub Timestamp,
+    /// Slot (Solana) or block height (NEAR) of the most recent update.
+    pub u64,
+    /// Number of updates of the feed so far, increments by one on every
+    /// update.
+    pub u64,
+);
 
 #[derive(Debug, Clone)]
 pub struct Payer {
@@ -39,14 +54,34 @@ impl OracleInfo {
         }
     }
 
-    pub async fn create(&self, name: &str, value: Option<u128>, payer: Option<Payer>) -> Result<()> {
+    /// The descriptive metadata of a feed, `None` if the owner never set it.
+    pub async fn get_metadata(&self, name: &str) -> Result<Option<FeedMetadata>> {
+        match self {
+            OracleInfo::Solana(net) => net.get_metadata(name),
+            OracleInfo::Near(net) => net.get_metadata(name).await,
+        }
+    }
+
+    /// Reads the value accumulator of a feed as of the latest block. The
+    /// time-weighted average value between two readings is given by
+    /// [`Observation::twap_since`].
+    ///
+    /// Time is counted in seconds on Solana and in milliseconds on NEAR.
+    pub async fn observe(&self, name: &str) -> Result<Observation> {
+        match self {
+            OracleInfo::Solana(net) => net.observe(name),
+            OracleInfo::Near(net) => net.observe(name).await,
+        }
+    }
+
+    pub async fn create(&self, name: &str, value: Option<i128>, payer: Option<Payer>) -> Result<()> {
         match self {
             OracleInfo::Solana(net) => net.create_feed(name, value, payer),
             OracleInfo::Near(net) => net.create_feed(name, value, payer.unwrap()).await,
         }
     }
 
-    pub async fn update(&self, name: &str, value: Option<u128>, payer: Option<Payer>) -> Result<()> {
+    pub async fn update(&self, name: &str, value: Option<i128>, payer: Option<Payer>) -> Result<()> {
         match self {
             OracleInfo::Solana(net) => net.update_feed(name, value, payer),
             OracleInfo::Near(net) => net.update_feed(name, value, payer.unwrap()).await,
@@ -67,6 +102,16 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn resolves_paths_like_the_client() {
+        let path = "equities.nasdaq.aapl.close.adjusted-for-splits-and-dividends";
+        assert!(path.len() > 32);
+        assert_eq!(
+            vvtec_client::OracleId::from_name(path),
+            vvtec_client::OracleId::from_address(solana::oracle_address(path)),
+        );
+    }
+
     // #[test]
     // fn on_solana() -> Result<()> {
     //     let oracle_info = OracleInfo::from_slug("solana", "devnet").unwrap();
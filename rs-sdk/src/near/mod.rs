@@ -1,4 +1,4 @@
-use crate::{OracleResponse, Payer};
+use crate::{FeedMetadata, Observation, OracleResponse, Payer};
 use anyhow::{anyhow, Result};
 use near_jsonrpc_client::{methods, JsonRpcClient};
 use near_jsonrpc_primitives::types::query::QueryResponseKind;
@@ -16,6 +16,30 @@ pub enum ReadResult {
     KnownFeed(Option<String>),
 }
 
+/// The metadata of a feed as returned by the contract.
+#[derive(Debug, Deserialize)]
+struct NearMetadata {
+    description: String,
+    unit: String,
+    decimals: u8,
+    sources: Vec<String>,
+    tags: Vec<String>,
+    website: String,
+}
+
+impl From<NearMetadata> for FeedMetadata {
+    fn from(metadata: NearMetadata) -> Self {
+        Self {
+            description: metadata.description,
+            unit: metadata.unit,
+            decimals: metadata.decimals,
+            sources: metadata.sources,
+            tags: metadata.tags,
+            website: metadata.website,
+        }
+    }
+}
+
 pub enum NearNetwork {
     Mainnet,
     Testnet,
@@ -46,7 +70,7 @@ impl NearNetwork {
         }
     }
 
-    pub async fn create_feed(&self, name: &str, value: Option<u128>, payer: Payer) -> Result<()> {
+    pub async fn create_feed(&self, name: &str, value: Option<i128>, payer: Payer) -> Result<()> {
         let client = JsonRpcClient::connect(format!("https://rpc.{}.near.org", self.slug()));
 
         let signer_account_id = payer.account_id.unwrap().parse()?;
@@ -112,7 +136,7 @@ impl NearNetwork {
         }
     }
 
-    pub async fn update_feed(&self, name: &str, value: Option<u128>, payer: Payer) -> Result<()> {
+    pub async fn update_feed(&self, name: &str, value: Option<i128>, payer: Payer) -> Result<()> {
         let client = JsonRpcClient::connect(format!("https://rpc.{}.near.org", self.slug()));
 
         let signer_account_id = payer.account_id.unwrap().parse()?;
@@ -246,15 +270,79 @@ impl NearNetwork {
 
         let response = client.call(request).await?;
         if let QueryResponseKind::CallResult(result) = response.kind {
-            let feed = from_slice::<(ReadResult, u64)>(&result.result)?;
+            let feed = from_slice::<(ReadResult, u64, Option<String>, String, String)>(
+                &result.result,
+            )?;
+            let confidence = feed.2.map(|c| c.parse::<u128>()).transpose()?;
+            let block_height = feed.3.parse::<u64>()?;
+            let round_id = feed.4.parse::<u64>()?;
             return match feed.0 {
                 ReadResult::KnownFeed(value) => match value {
-                    Some(v) => Ok(OracleResponse(Some(v.parse::<u128>().unwrap()), feed.1 as i64)),
-                    None => Ok(OracleResponse(None, feed.1 as i64)),
+                    Some(v) => Ok(OracleResponse(
+                        Some(v.parse::<i128>().unwrap()),
+                        confidence,
+                        feed.1 as i64,
+                        block_height,
+                        round_id,
+                    )),
+                    None => Ok(OracleResponse(
+                        None,
+                        confidence,
+                        feed.1 as i64,
+                        block_height,
+                        round_id,
+                    )),
                 },
                 ReadResult::UnknownFeed => Err(anyhow!("unknown feed")),
             };
         };
         Err(anyhow!("parsing error"))
     }
+
+    pub async fn observe(&self, name: &str) -> Result<Observation> {
+        let client = JsonRpcClient::connect(format!("https://rpc.{}.near.org", self.slug()));
+        let request = methods::query::RpcQueryRequest {
+            block_reference: BlockReference::Finality(Finality::Final),
+            request: QueryRequest::CallFunction {
+                account_id: Self::CONTRACT_ID.parse()?,
+                method_name: "observe".to_string(),
+                args: FunctionArgs::from(json!({ "name": name }).to_string().into_bytes()),
+            },
+        };
+
+        let response = client.call(request).await?;
+        if let QueryResponseKind::CallResult(result) = response.kind {
+            let (cumulative_value, timestamp) =
+                from_slice::<Option<(String, u64)>>(&result.result)?
+                    .ok_or_else(|| anyhow!("unknown feed"))?;
+            // the accumulator wraps around, only differences are meaningful
+            return Ok(Observation {
+                cumulative_value: cumulative_value.parse::<i128>()?,
+                timestamp: timestamp as i64,
+            });
+        };
+        Err(anyhow!("parsing error"))
+    }
+
+    pub async fn get_metadata(&self, name: &str) -> Result<Option<FeedMetadata>> {
+        let client = JsonRpcClient::connect(format!("https://rpc.{}.near.org", self.slug()));
+        let request = methods::query::RpcQueryRequest {
+            block_reference: BlockReference::Finality(Finality::Final),
+            request: QueryRequest::CallFunction {
+                account_id: Self::CONTRACT_ID.parse()?,
+                method_name: "metadata".to_string(),
+                args: FunctionArgs::from(json!({ "name": name }).to_string().into_bytes()),
+            },
+        };
+
+        let response = client.call(request).await?;
+        if let QueryResponseKind::CallResult(result) = response.kind {
+            let metadata = from_slice::<Option<NearMetadata>>(&result.result)?
+                .ok_or_else(|| anyhow!("unknown feed"))?;
+            let metadata = FeedMetadata::from(metadata);
+            // every NEAR feed carries metadata, unset fields are empty
+            return Ok(Some(metadata).filter(|m| m != &FeedMetadata::default()));
+        };
+        Err(anyhow!("parsing error"))
+    }
 }
@@ -3,14 +3,19 @@ use std::{rc::Rc, str::FromStr};
 
 use anchor_client::{
     solana_sdk::{
+        account::from_account,
+        clock::Clock,
         commitment_config::CommitmentConfig,
         pubkey::Pubkey,
         signature::{read_keypair_file, Keypair},
-        system_program,
+        system_program, sysvar,
     },
-    Client, Cluster,
+    Client, ClientError, Cluster, Program,
+};
+use vvtec::{
+    state::{find_path_address, Config, FeedMetadata, Metadata, Observation, Oracle, UpdatePolicy},
+    Feed,
 };
-use vvtec::{state::Oracle, Feed};
 
 use crate::{OracleResponse, Payer};
 
@@ -28,6 +33,21 @@ pub fn load_payer_from_secret_key(secret_key: &str) -> Result<Keypair> {
     Ok(payer)
 }
 
+/// The address of the feed at the dotted `name` in the global namespace,
+/// resolved the same way as by the client.
+pub fn oracle_address(name: &str) -> Pubkey {
+    find_path_address(&[], name)
+}
+
+fn config_address() -> Pubkey {
+    Pubkey::find_program_address(&[Config::SEED], &vvtec::id()).0
+}
+
+fn treasury(program: &Program) -> Result<Pubkey> {
+    let config: Config = program.account(config_address())?;
+    Ok(config.treasury)
+}
+
 pub enum SolanaNetwork {
     Testnet,
     Devnet,
@@ -56,13 +76,7 @@ impl SolanaNetwork {
         }
     }
 
-    fn get_name_bytes(&self, name: &str) -> [u8; 32] {
-        let mut name_bytes: [u8; 32] = Default::default();
-        name_bytes[..name.len()].copy_from_slice(&name.as_bytes());
-        return name_bytes;
-    }
-
-    pub fn create_feed(&self, name: &str, value: Option<u128>, payer_sk: Option<Payer>) -> Result<()> {
+    pub fn create_feed(&self, name: &str, value: Option<i128>, payer_sk: Option<Payer>) -> Result<()> {
         let payer = match payer_sk {
             None => load_payer_from_solana_config()?,
             Some(sk) => load_payer_from_secret_key(&sk.secret_key)?,
@@ -74,22 +88,45 @@ impl SolanaNetwork {
 
         let program = client.program(vvtec::id());
 
-        let name_bytes = self.get_name_bytes(name);
-        let oracle = Pubkey::find_program_address(&[&name_bytes], &vvtec::id()).0;
+        let oracle = oracle_address(name);
 
+        // dotted names are attached to the feed at the parent path
+        let (parent, leaf) = match name.rsplit_once('.') {
+            Some((parent, leaf)) => (Some(oracle_address(parent)), leaf),
+            None => (None, name),
+        };
         let feed = Feed {
             owner: program.payer(),
-            name: name_bytes,
+            name: leaf.to_owned(),
             value,
+            exponent: 0,
+            policy: UpdatePolicy::default(),
+            history_capacity: 0,
+        };
+        let req = match parent {
+            Some(parent) => program
+                .request()
+                .accounts(vvtec::accounts::AttachChild {
+                    payer: program.payer(),
+                    owner: program.payer(),
+                    parent,
+                    child: oracle,
+                    config: config_address(),
+                    treasury: treasury(&program)?,
+                    system_program: system_program::ID,
+                })
+                .args(vvtec::instruction::AttachChild { feed }),
+            None => program
+                .request()
+                .accounts(vvtec::accounts::Create {
+                    payer: program.payer(),
+                    oracle,
+                    config: config_address(),
+                    treasury: treasury(&program)?,
+                    system_program: system_program::ID,
+                })
+                .args(vvtec::instruction::Create { feed }),
         };
-        let req = program
-            .request()
-            .accounts(vvtec::accounts::Create {
-                payer: program.payer(),
-                oracle,
-                system_program: system_program::ID,
-            })
-            .args(vvtec::instruction::Create { feed });
 
         match req.send() {
             Ok(_) => {
@@ -99,7 +136,7 @@ impl SolanaNetwork {
         }
     }
 
-    pub fn update_feed(&self, name: &str, value: Option<u128>, payer_sk: Option<Payer>) -> Result<()> {
+    pub fn update_feed(&self, name: &str, value: Option<i128>, payer_sk: Option<Payer>) -> Result<()> {
         let payer = match payer_sk {
             None => load_payer_from_solana_config()?,
             Some(sk) => load_payer_from_secret_key(&sk.secret_key)?,
@@ -111,16 +148,21 @@ impl SolanaNetwork {
 
         let program = client.program(vvtec::id());
 
-        let name_bytes = self.get_name_bytes(name);
-        let oracle = Pubkey::find_program_address(&[&name_bytes], &vvtec::id()).0;
+        let oracle = oracle_address(name);
 
         let req = program
             .request()
             .accounts(vvtec::accounts::Update {
                 owner: program.payer(),
                 oracle,
+                config: config_address(),
+                treasury: treasury(&program)?,
+                system_program: system_program::ID,
             })
-            .args(vvtec::instruction::Update { value });
+            .args(vvtec::instruction::Update {
+                value,
+                confidence: None,
+            });
 
         match req.send() {
             Ok(_) => {
@@ -142,16 +184,32 @@ impl SolanaNetwork {
 
         let program = client.program(vvtec::id());
 
-        let name_bytes = self.get_name_bytes(name);
-        let oracle = Pubkey::find_program_address(&[&name_bytes], &vvtec::id()).0;
-
-        let req = program
-            .request()
-            .accounts(vvtec::accounts::Delete {
-                owner: program.payer(),
-                oracle,
-            })
-            .args(vvtec::instruction::Delete {});
+        let oracle = oracle_address(name);
+
+        // child feeds are detached from the feed at the parent path
+        let req = match name.rsplit_once('.') {
+            Some((parent, _)) => {
+                let data = program.rpc().get_account_data(&oracle)?;
+                let child = Oracle::from_account_data(&data).map_err(|e| anyhow!("{}", e))?;
+                program
+                    .request()
+                    .accounts(vvtec::accounts::DetachChild {
+                        owner: program.payer(),
+                        parent: oracle_address(parent),
+                        child: oracle,
+                        child_owner: child.owner,
+                    })
+                    .args(vvtec::instruction::DetachChild {})
+            }
+            None => program
+                .request()
+                .accounts(vvtec::accounts::Delete {
+                    owner: program.payer(),
+                    oracle,
+                    recipient: program.payer(),
+                })
+                .args(vvtec::instruction::Delete {}),
+        };
 
         match req.send() {
             Ok(_) => Ok(()),
@@ -168,10 +226,55 @@ impl SolanaNetwork {
 
         let program = client.program(vvtec::id());
 
-        let name_bytes = self.get_name_bytes(name);
-        let oracle_id = Pubkey::find_program_address(&[&name_bytes], &vvtec::id()).0;
-        let feed: Oracle = program.account(oracle_id)?;
+        let oracle_id = oracle_address(name);
+        let data = program.rpc().get_account_data(&oracle_id)?;
+        let feed = Oracle::from_account_data(&data).map_err(|e| anyhow!("{}", e))?;
+
+        Ok(OracleResponse(
+            feed.value,
+            feed.confidence,
+            feed.updated_at,
+            feed.slot,
+            feed.round_id,
+        ))
+    }
+
+    pub fn observe(&self, name: &str) -> Result<Observation> {
+        let payer = load_payer_from_solana_config()?;
+        let cluster = Cluster::from_str(self.slug())?;
+
+        let client =
+            Client::new_with_options(cluster, Rc::new(payer), CommitmentConfig::processed());
+
+        let program = client.program(vvtec::id());
+
+        let oracle_id = oracle_address(name);
+        let data = program.rpc().get_account_data(&oracle_id)?;
+        let feed = Oracle::from_account_data(&data).map_err(|e| anyhow!("{}", e))?;
+
+        let clock_account = program.rpc().get_account(&sysvar::clock::ID)?;
+        let clock: Clock = from_account(&clock_account).ok_or_else(|| anyhow!("clock sysvar"))?;
+
+        Ok(feed.observe(clock.unix_timestamp))
+    }
+
+    pub fn get_metadata(&self, name: &str) -> Result<Option<FeedMetadata>> {
+        let payer = load_payer_from_solana_config()?;
+        let cluster = Cluster::from_str(self.slug())?;
+
+        let client =
+            Client::new_with_options(cluster, Rc::new(payer), CommitmentConfig::processed());
+
+        let program = client.program(vvtec::id());
 
-        Ok(OracleResponse(feed.value, feed.updated_at))
+        let oracle_id = oracle_address(name);
+        let metadata_id =
+            Pubkey::find_program_address(&[Metadata::SEED, oracle_id.as_ref()], &vvtec::id()).0;
+
+        match program.account::<Metadata>(metadata_id) {
+            Ok(metadata) => Ok(Some(metadata.info)),
+            Err(ClientError::AccountNotFound) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
     }
 }
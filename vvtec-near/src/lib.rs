@@ -2,13 +2,25 @@ use near_sdk::{
   borsh::{self, BorshDeserialize, BorshSerialize},
   collections::UnorderedMap,
   env,
-  json_types::{U64, U128},
+  json_types::{I128, U64, U128},
   near_bindgen,
   serde::{Deserialize, Serialize},
   AccountId, BorshStorageKey,
 };
 
-const FEED_NAME_MAX_LEN: usize = 32;
+const FEED_NAME_MAX_LEN: usize = 128;
+
+const DESCRIPTION_MAX_LEN: usize = 256;
+
+const UNIT_MAX_LEN: usize = 16;
+
+/// Maximum number of entries in the sources and in the tags of a feed.
+const METADATA_MAX_ENTRIES: usize = 8;
+
+/// Maximum length of a single source or tag.
+const METADATA_ENTRY_MAX_LEN: usize = 32;
+
+const WEBSITE_MAX_LEN: usize = 128;
 
 #[derive(BorshSerialize, BorshStorageKey)]
 enum StorageKey {
@@ -34,10 +46,41 @@ struct Feed {
   /// automatically during updates.
   pub updated_at: u64,
 
+  /// The block height of the most recent update of the feed value.
+  pub block_height: u64,
+
+  /// The number of updates of the feed value so far, starting at one for the
+  /// value written at creation.
+  pub round_id: u64,
+
   /// The value that is stored within a single feed. In most cases, intermediate
   /// nodes or non-leaf feeds will be None (although some may decide to have a
   /// summary value for their children), and leaf feeds will have concrete values.
-  pub value: Option<U128>,
+  pub value: Option<I128>,
+
+  /// The uncertainty of the value. The actual value is expected to lie within
+  /// `value ± confidence`.
+  pub confidence: Option<U128>,
+
+  /// The running sum of `value * milliseconds` over the lifetime of the feed,
+  /// up to `updated_at`. Time without a value adds nothing.
+  ///
+  /// The sum wraps around on overflow, so only the difference between two
+  /// observations is meaningful, see [`Vvtec::observe`].
+  pub cumulative_value: i128,
+
+  /// Descriptive information about the feed, edited by the owner.
+  pub metadata: FeedMetadata,
+}
+
+impl Feed {
+  /// The value accumulator extended up to the timestamp `now`, as if the
+  /// current value was held until then.
+  fn cumulative_value_at(&self, now: u64) -> i128 {
+    let held = now.saturating_sub(self.updated_at) as i128;
+    let value = self.value.map_or(0, |v| v.0);
+    self.cumulative_value.wrapping_add(value.wrapping_mul(held))
+  }
 }
 
 /// Onchain state.
@@ -50,10 +93,11 @@ pub struct Vvtec {
 
 #[near_bindgen]
 impl Vvtec {
-  pub fn create(&mut self, name: String, value: Option<U128>) {
+  pub fn create(&mut self, name: String, value: Option<I128>) {
     assert!(
       name.len() <= FEED_NAME_MAX_LEN,
-      "Feed name must be less than 32 characters"
+      "Feed name must be at most {} characters",
+      FEED_NAME_MAX_LEN
     );
     assert!(
       is_valid_name(name.as_bytes()),
@@ -64,7 +108,12 @@ impl Vvtec {
       owner: env::signer_account_id(),
       name,
       updated_at: env::block_timestamp_ms(),
+      block_height: env::block_height(),
+      round_id: 1,
       value,
+      confidence: None,
+      cumulative_value: 0,
+      metadata: FeedMetadata::default(),
     };
 
     assert!(
@@ -73,7 +122,12 @@ impl Vvtec {
     );
   }
 
-  pub fn update(&mut self, name: String, value: Option<U128>) {
+  pub fn update(
+    &mut self,
+    name: String,
+    value: Option<I128>,
+    confidence: Option<U128>,
+  ) {
     let mut feed = self.feeds.get(&name).expect("Unknown feed");
     assert_eq!(
       feed.owner,
@@ -81,9 +135,28 @@ impl Vvtec {
       "Missing feed owner signature"
     );
 
+    feed.cumulative_value = feed.cumulative_value_at(env::block_timestamp_ms());
     feed.updated_at = env::block_timestamp_ms();
+    feed.block_height = env::block_height();
+    feed.round_id += 1;
     feed.value = value;
+    feed.confidence = confidence;
+
+    self.feeds.insert(&name, &feed);
+  }
+
+  /// Replaces the metadata of a feed, only the owner of the feed can call
+  /// this.
+  pub fn set_metadata(&mut self, name: String, metadata: FeedMetadata) {
+    let mut feed = self.feeds.get(&name).expect("Unknown feed");
+    assert_eq!(
+      feed.owner,
+      env::signer_account_id(),
+      "Missing feed owner signature"
+    );
+    metadata.validate();
 
+    feed.metadata = metadata;
     self.feeds.insert(&name, &feed);
   }
 
@@ -98,13 +171,43 @@ impl Vvtec {
     self.feeds.remove(&name);
   }
 
-  pub fn read(&self, name: String) -> (ReadResult, u64) {
+  /// Returns the value of a feed, the timestamp of its most recent update,
+  /// the confidence of the value, the block height of the update and the
+  /// round id of the value.
+  pub fn read(
+    &self,
+    name: String,
+  ) -> (ReadResult, u64, Option<U128>, U64, U64) {
     match self.feeds.get(&name) {
-      Some(feed) => (ReadResult::KnownFeed(feed.value), feed.updated_at),
-      None => (ReadResult::UnknownFeed, 0),
+      Some(feed) => (
+        ReadResult::KnownFeed(feed.value),
+        feed.updated_at,
+        feed.confidence,
+        U64(feed.block_height),
+        U64(feed.round_id),
+      ),
+      None => (ReadResult::UnknownFeed, 0, None, U64(0), U64(0)),
     }
   }
 
+  /// Returns the metadata of a feed, or `None` for an unknown feed.
+  pub fn metadata(&self, name: String) -> Option<FeedMetadata> {
+    self.feeds.get(&name).map(|feed| feed.metadata)
+  }
+
+  /// Returns the value accumulator of a feed as of the current block along
+  /// with the block timestamp, or `None` for an unknown feed.
+  ///
+  /// The time-weighted average value between two observations is the
+  /// difference of their accumulators divided by the time in between.
+  pub fn observe(&self, name: String) -> Option<(I128, u64)> {
+    let now = env::block_timestamp_ms();
+    self
+      .feeds
+      .get(&name)
+      .map(|feed| (I128(feed.cumulative_value_at(now)), now))
+  }
+
   pub fn num_feeds(&self) -> U64 {
     U64(self.feeds.len())
   }
@@ -115,7 +218,63 @@ impl Vvtec {
 )]
 pub enum ReadResult {
   UnknownFeed,
-  KnownFeed(Option<U128>),
+  KnownFeed(Option<I128>),
+}
+
+/// The descriptive fields of a feed, all of them optional. Empty strings and
+/// lists mean the field is not set.
+#[derive(
+  Debug,
+  Clone,
+  Default,
+  Eq,
+  PartialEq,
+  BorshDeserialize,
+  BorshSerialize,
+  Serialize,
+  Deserialize,
+)]
+pub struct FeedMetadata {
+  /// What the value of the feed represents.
+  pub description: String,
+
+  /// The unit of the value, e.g. `USD` or `°C`.
+  pub unit: String,
+
+  /// The number of decimal places the value is meant to be displayed with.
+  pub decimals: u8,
+
+  /// Where the value comes from, e.g. the names of exchanges or APIs.
+  pub sources: Vec<String>,
+
+  /// Category tags, e.g. `crypto` or `weather`.
+  pub tags: Vec<String>,
+
+  /// A URL with more information about the feed.
+  pub website: String,
+}
+
+impl FeedMetadata {
+  /// Panics unless every field is within its limit, string lengths are
+  /// counted in bytes.
+  fn validate(&self) {
+    assert!(
+      self.description.len() <= DESCRIPTION_MAX_LEN,
+      "Feed description is too long"
+    );
+    assert!(self.unit.len() <= UNIT_MAX_LEN, "Feed unit is too long");
+    for entries in [&self.sources, &self.tags] {
+      assert!(
+        entries.len() <= METADATA_MAX_ENTRIES
+          && entries.iter().all(|e| e.len() <= METADATA_ENTRY_MAX_LEN),
+        "Feed sources and tags exceed their limits"
+      );
+    }
+    assert!(
+      self.website.len() <= WEBSITE_MAX_LEN,
+      "Feed website is too long"
+    );
+  }
 }
 
 impl Default for Vvtec {
@@ -127,7 +286,7 @@ impl Default for Vvtec {
 }
 
 fn is_valid_name(bytes: &[u8]) -> bool {
-  bytes.len() <= 32
+  bytes.len() <= FEED_NAME_MAX_LEN
     && bytes.iter().all(|b| {
       *b == b'_'
         || *b == b'.'
@@ -1,6 +1,6 @@
-use near_sdk::json_types::{U64, U128};
+use near_sdk::json_types::{I128, U64, U128};
 
-use vvtec_near::ReadResult;
+use vvtec_near::{FeedMetadata, ReadResult};
 // macro allowing us to convert args into JSON bytes to be read by the contract.
 use serde_json::json;
 
@@ -16,7 +16,7 @@ async fn read<T: NetworkClient + NetworkInfo + Send + Sync>(
   contract: &Contract,
   worker: &Worker<T>,
   name: &str,
-) -> (ReadResult, u64) {
+) -> (ReadResult, u64, Option<U128>, U64, U64) {
   contract
     .call(worker, "read")
     .args_json(json!({ "name": name }))
@@ -33,7 +33,7 @@ async fn create<T: NetworkClient + NetworkInfo + Send + Sync>(
   contract: &Contract,
   worker: &Worker<T>,
   name: &str,
-  value: Option<U128>,
+  value: Option<I128>,
 ) -> anyhow::Result<CallExecutionDetails> {
   account
     .call(&worker, contract.id(), "create")
@@ -47,11 +47,12 @@ async fn update<T: NetworkClient + NetworkInfo + Send + Sync>(
   contract: &Contract,
   worker: &Worker<T>,
   name: &str,
-  value: Option<U128>,
+  value: Option<I128>,
+  confidence: Option<U128>,
 ) -> anyhow::Result<CallExecutionDetails> {
   account
     .call(&worker, contract.id(), "update")
-    .args_json(json!({"name": name, "value": value}))?
+    .args_json(json!({"name": name, "value": value, "confidence": confidence}))?
     .transact()
     .await
 }
@@ -69,6 +70,52 @@ async fn delete<T: NetworkClient + NetworkInfo + Send + Sync>(
     .await
 }
 
+async fn observe<T: NetworkClient + NetworkInfo + Send + Sync>(
+  contract: &Contract,
+  worker: &Worker<T>,
+  name: &str,
+) -> Option<(I128, u64)> {
+  contract
+    .call(worker, "observe")
+    .args_json(json!({ "name": name }))
+    .unwrap()
+    .view()
+    .await
+    .unwrap()
+    .json()
+    .unwrap()
+}
+
+async fn metadata<T: NetworkClient + NetworkInfo + Send + Sync>(
+  contract: &Contract,
+  worker: &Worker<T>,
+  name: &str,
+) -> Option<FeedMetadata> {
+  contract
+    .call(worker, "metadata")
+    .args_json(json!({ "name": name }))
+    .unwrap()
+    .view()
+    .await
+    .unwrap()
+    .json()
+    .unwrap()
+}
+
+async fn set_metadata<T: NetworkClient + NetworkInfo + Send + Sync>(
+  account: &Account,
+  contract: &Contract,
+  worker: &Worker<T>,
+  name: &str,
+  metadata: &FeedMetadata,
+) -> anyhow::Result<CallExecutionDetails> {
+  account
+    .call(&worker, contract.id(), "set_metadata")
+    .args_json(json!({"name": name, "metadata": metadata}))?
+    .transact()
+    .await
+}
+
 async fn num_feeds<T: NetworkClient + NetworkInfo + Send + Sync>(
   contract: &Contract,
   worker: &Worker<T>,
@@ -121,7 +168,7 @@ async fn test_contract() -> anyhow::Result<()> {
     &contract,
     &worker,
     "feed1",
-    Some(U128(1)),
+    Some(I128(1)),
   )
   .await
   .unwrap();
@@ -129,7 +176,7 @@ async fn test_contract() -> anyhow::Result<()> {
   // check initial feed value
   assert_eq!(
     read(&contract, &worker, "feed1").await.0,
-    ReadResult::KnownFeed(Some(U128(1)))
+    ReadResult::KnownFeed(Some(I128(1)))
   );
 
   // Create another feed.
@@ -138,7 +185,7 @@ async fn test_contract() -> anyhow::Result<()> {
     &contract,
     &worker,
     "feed2",
-    Some(U128(u128::MAX)),
+    Some(I128(i128::MIN)),
   )
   .await
   .unwrap();
@@ -146,7 +193,7 @@ async fn test_contract() -> anyhow::Result<()> {
   // check initial feed value
   assert_eq!(
     read(&contract, &worker, "feed2").await.0,
-    ReadResult::KnownFeed(Some(U128(u128::MAX)))
+    ReadResult::KnownFeed(Some(I128(i128::MIN)))
   );
 
   // Create third feed.
@@ -169,7 +216,7 @@ async fn test_contract() -> anyhow::Result<()> {
     &contract,
     &worker,
     "feed1",
-    Some(U128(0)),
+    Some(I128(0)),
   )
   .await;
   assert!(result
@@ -180,29 +227,101 @@ async fn test_contract() -> anyhow::Result<()> {
   // check that feed1 value is not affected
   assert_eq!(
     read(&contract, &worker, "feed1").await.0,
-    ReadResult::KnownFeed(Some(U128(1)))
+    ReadResult::KnownFeed(Some(I128(1)))
   );
 
   // There are still three feeds
   assert_eq!(num_feeds(&contract, &worker).await.0, 3);
 
+  // Names are capped at 128 characters.
+  let result =
+    create(&root_account, &contract, &worker, &"x".repeat(129), None).await;
+  assert!(result
+    .unwrap_err()
+    .to_string()
+    .contains("Feed name must be at most 128 characters"));
+
   // Update feed value
   update(
     &root_account,
     &contract,
     &worker,
     "feed1",
-    Some(U128(0)),
+    Some(I128(0)),
+    None,
   )
   .await
   .unwrap();
   assert_eq!(
     read(&contract, &worker, "feed1").await.0,
-    ReadResult::KnownFeed(Some(U128(0)))
+    ReadResult::KnownFeed(Some(I128(0)))
   );
 
+  // Update feed value along with its confidence
+  update(
+    &root_account,
+    &contract,
+    &worker,
+    "feed1",
+    Some(I128(100)),
+    Some(U128(5)),
+  )
+  .await
+  .unwrap();
+  let (value, _, confidence, block_height, round_id) =
+    read(&contract, &worker, "feed1").await;
+  assert_eq!(value, ReadResult::KnownFeed(Some(I128(100))));
+  assert_eq!(confidence, Some(U128(5)));
+
+  // Every write advances the round id, starting at one on creation.
+  assert_eq!(round_id, U64(3));
+  assert!(block_height.0 > 0);
+
+  // The accumulator grows by the value held since the last update.
+  let updated_at = read(&contract, &worker, "feed1").await.1;
+  let (earlier, earlier_at) =
+    observe(&contract, &worker, "feed1").await.unwrap();
+  worker.fast_forward(10).await?;
+  let (later, later_at) = observe(&contract, &worker, "feed1").await.unwrap();
+  assert!(later_at > earlier_at && earlier_at >= updated_at);
+  assert_eq!(
+    later.0.wrapping_sub(earlier.0),
+    100 * (later_at - earlier_at) as i128
+  );
+  assert_eq!(observe(&contract, &worker, "dummy").await, None);
+
+  // Feeds start without metadata, owners can describe them.
+  assert_eq!(
+    metadata(&contract, &worker, "feed1").await,
+    Some(FeedMetadata::default())
+  );
+  let described = FeedMetadata {
+    description: "Test feed".to_owned(),
+    unit: "USD".to_owned(),
+    decimals: 2,
+    sources: vec!["manual".to_owned()],
+    tags: vec!["test".to_owned()],
+    website: "https://vvtec.network".to_owned(),
+  };
+  set_metadata(&root_account, &contract, &worker, "feed1", &described)
+    .await
+    .unwrap();
+  assert_eq!(
+    metadata(&contract, &worker, "feed1").await,
+    Some(described.clone())
+  );
+  let result =
+    set_metadata(&root_account, &contract, &worker, "feed2", &described)
+      .await;
+  assert!(result
+    .unwrap_err()
+    .to_string()
+    .contains("Missing feed owner signature"));
+  assert_eq!(metadata(&contract, &worker, "dummy").await, None);
+
   // Only owner should be able to update a feed.
-  let result = update(&root_account, &contract, &worker, "feed2", None).await;
+  let result =
+    update(&root_account, &contract, &worker, "feed2", None, None).await;
   assert!(result
     .unwrap_err()
     .to_string()
@@ -214,7 +333,8 @@ async fn test_contract() -> anyhow::Result<()> {
     &contract,
     &worker,
     "feed4",
-    Some(U128(0)),
+    Some(I128(0)),
+    None,
   )
   .await;
   assert!(result.unwrap_err().to_string().contains("Unknown feed"));